@@ -0,0 +1,17 @@
+fn main() {
+    #[cfg(feature = "napi")]
+    napi_build::setup();
+
+    // The generated Swift and C header land in OUT_DIR unless
+    // SWIFT_BRIDGE_OUT_DIR points an Xcode project somewhere stable.
+    #[cfg(feature = "swift")]
+    {
+        println!("cargo:rerun-if-changed=src/swift.rs");
+        println!("cargo:rerun-if-env-changed=SWIFT_BRIDGE_OUT_DIR");
+        let out_dir = std::env::var("SWIFT_BRIDGE_OUT_DIR")
+            .or_else(|_| std::env::var("OUT_DIR").map(|dir| format!("{dir}/swift-bridge")))
+            .expect("cargo sets OUT_DIR");
+        swift_bridge_build::parse_bridges(["src/swift.rs"])
+            .write_all_concatenated(out_dir, env!("CARGO_PKG_NAME"));
+    }
+}
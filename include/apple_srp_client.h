@@ -0,0 +1,57 @@
+#ifndef APPLE_SRP_CLIENT_H
+#define APPLE_SRP_CLIENT_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+/* Flat handle-based API behind the `capi` feature. Byte results are written to
+ * caller-owned buffers of SRP_BUFFER_LEN bytes; functions return the number of
+ * bytes written (or SRP_OK) and a negative SRP_ERR_* code on failure. */
+
+#define SRP_BUFFER_LEN 512
+
+#define SRP_OK 0
+#define SRP_ERR_NULL_POINTER -1
+#define SRP_ERR_ILLEGAL_PARAMETER -2
+#define SRP_ERR_BAD_RECORD_MAC -3
+#define SRP_ERR_DECODE -4
+#define SRP_ERR_UNKNOWN_NAME -5
+#define SRP_ERR_BUFFER_TOO_SMALL -6
+
+typedef struct SrpClientHandle SrpClientHandle;
+typedef struct SrpVerifierHandle SrpVerifierHandle;
+
+/* NULL profile or digest selects the defaults. */
+int32_t srp_client_new(const char *profile, const char *digest, SrpClientHandle **out);
+void srp_client_free(SrpClientHandle *client);
+
+int32_t srp_compute_verifier(const SrpClientHandle *client,
+                             const uint8_t *username, size_t username_len,
+                             const uint8_t *password, size_t password_len,
+                             const uint8_t *salt, size_t salt_len,
+                             uint8_t *out);
+
+int32_t srp_compute_public_ephemeral(const SrpClientHandle *client,
+                                     const uint8_t *a, size_t a_len,
+                                     uint8_t *out);
+
+/* The verifier must be passed to srp_verifier_verify_server or
+ * srp_verifier_free exactly once. */
+int32_t srp_process_reply(const SrpClientHandle *client,
+                          const uint8_t *a, size_t a_len,
+                          const uint8_t *username, size_t username_len,
+                          const uint8_t *password, size_t password_len,
+                          const uint8_t *salt, size_t salt_len,
+                          const uint8_t *b_pub, size_t b_pub_len,
+                          SrpVerifierHandle **out);
+
+int32_t srp_verifier_proof(const SrpVerifierHandle *verifier, uint8_t *out);
+
+/* Writes the session key; consumes the verifier even on failure. */
+int32_t srp_verifier_verify_server(SrpVerifierHandle *verifier,
+                                   const uint8_t *m2, size_t m2_len,
+                                   uint8_t *out);
+
+void srp_verifier_free(SrpVerifierHandle *verifier);
+
+#endif
@@ -0,0 +1,8 @@
+package io.github.kekeimiku.srp;
+
+// Thrown for every SrpAuthError reported by the native library.
+public class SrpAuthException extends RuntimeException {
+    public SrpAuthException(String message) {
+        super(message);
+    }
+}
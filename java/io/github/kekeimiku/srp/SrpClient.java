@@ -0,0 +1,95 @@
+package io.github.kekeimiku.srp;
+
+// Java side of the `jni` feature. Load the native library first, e.g. with
+// System.loadLibrary("apple_srp_client") on Android. Close both the client and
+// any verifier that is not handed to verifyServer.
+public final class SrpClient implements AutoCloseable {
+    private long handle;
+
+    public SrpClient() {
+        this(null, null);
+    }
+
+    // A null profile or digest selects the default profile or that profile's
+    // digest.
+    public SrpClient(String profile, String digest) {
+        handle = nativeNew(profile, digest);
+    }
+
+    public byte[] computeVerifier(byte[] username, byte[] password, byte[] salt) {
+        return nativeComputeVerifier(live(), username, password, salt);
+    }
+
+    public byte[] computePublicEphemeral(byte[] a) {
+        return nativeComputePublicEphemeral(live(), a);
+    }
+
+    public Verifier processReply(
+            byte[] a, byte[] username, byte[] password, byte[] salt, byte[] bPub) {
+        return new Verifier(nativeProcessReply(live(), a, username, password, salt, bPub));
+    }
+
+    @Override
+    public synchronized void close() {
+        nativeFree(handle);
+        handle = 0;
+    }
+
+    private synchronized long live() {
+        if (handle == 0) {
+            throw new IllegalStateException("client already closed");
+        }
+        return handle;
+    }
+
+    public static final class Verifier implements AutoCloseable {
+        private long handle;
+
+        private Verifier(long handle) {
+            this.handle = handle;
+        }
+
+        public byte[] proof() {
+            return nativeVerifierProof(live());
+        }
+
+        // Returns the session key. The verifier is consumed whether or not
+        // the server proof is accepted.
+        public synchronized byte[] verifyServer(byte[] m2) {
+            long verifier = live();
+            handle = 0;
+            return nativeVerifyServer(verifier, m2);
+        }
+
+        @Override
+        public synchronized void close() {
+            nativeVerifierFree(handle);
+            handle = 0;
+        }
+
+        private synchronized long live() {
+            if (handle == 0) {
+                throw new IllegalStateException("verifier already consumed");
+            }
+            return handle;
+        }
+    }
+
+    private static native long nativeNew(String profile, String digest);
+
+    private static native void nativeFree(long handle);
+
+    private static native byte[] nativeComputeVerifier(
+            long handle, byte[] username, byte[] password, byte[] salt);
+
+    private static native byte[] nativeComputePublicEphemeral(long handle, byte[] a);
+
+    private static native long nativeProcessReply(
+            long handle, byte[] a, byte[] username, byte[] password, byte[] salt, byte[] bPub);
+
+    private static native byte[] nativeVerifierProof(long verifier);
+
+    private static native byte[] nativeVerifyServer(long verifier, byte[] m2);
+
+    private static native void nativeVerifierFree(long verifier);
+}
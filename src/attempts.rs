@@ -0,0 +1,212 @@
+use std::{
+    cell::RefCell,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{HapError, PakeClient, SrpAuthError};
+
+pub const MAX_PAIR_SETUP_ATTEMPTS: u32 = 100;
+
+const ENCODED_LEN: usize = 12;
+
+// How many failures are free, how the wait grows after that and when to give
+// up entirely. The wait after the n-th paid failure is `base_backoff * 2^n`,
+// capped at `max_backoff`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThrottlePolicy {
+    pub free_attempts: u32,
+    pub max_attempts: u32,
+    pub base_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl ThrottlePolicy {
+    pub const PAIR_SETUP: Self = Self {
+        free_attempts: 3,
+        max_attempts: MAX_PAIR_SETUP_ATTEMPTS,
+        base_backoff: Duration::from_secs(1),
+        max_backoff: Duration::from_secs(60 * 60),
+    };
+
+    fn backoff(&self, failures: u32) -> Duration {
+        let exponent = failures.saturating_sub(self.free_attempts);
+        if exponent == 0 {
+            return Duration::ZERO;
+        }
+        self.base_backoff
+            .saturating_mul(1 << exponent.min(31))
+            .min(self.max_backoff)
+    }
+}
+
+impl Default for ThrottlePolicy {
+    fn default() -> Self {
+        Self::PAIR_SETUP
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttemptDecision {
+    Allowed,
+    Backoff(Duration),
+    LockedOut,
+}
+
+impl AttemptDecision {
+    pub fn error(self) -> Option<HapError> {
+        match self {
+            AttemptDecision::Allowed => None,
+            AttemptDecision::Backoff(_) => Some(HapError::Backoff),
+            AttemptDecision::LockedOut => Some(HapError::MaxTries),
+        }
+    }
+}
+
+// Failed Pair-Setup attempts since the last success. After a few free tries
+// each failure doubles the wait before the next one, and after 100 the
+// accessory refuses Pair-Setup until it is reset. The state is twelve bytes so
+// it can be persisted next to the accessory's pairings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AttemptTracker {
+    failures: u32,
+    last_failure: u64,
+}
+
+impl AttemptTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+
+    pub fn check(&self, now: SystemTime) -> AttemptDecision {
+        self.check_with(&ThrottlePolicy::PAIR_SETUP, now)
+    }
+
+    pub fn check_with(&self, policy: &ThrottlePolicy, now: SystemTime) -> AttemptDecision {
+        if self.failures >= policy.max_attempts {
+            return AttemptDecision::LockedOut;
+        }
+        let backoff = policy.backoff(self.failures);
+        if backoff.is_zero() {
+            return AttemptDecision::Allowed;
+        }
+
+        let retry_at = UNIX_EPOCH + Duration::from_secs(self.last_failure) + backoff;
+        match retry_at.duration_since(now) {
+            Ok(remaining) if !remaining.is_zero() => AttemptDecision::Backoff(remaining),
+            _ => AttemptDecision::Allowed,
+        }
+    }
+
+    pub fn record_failure(&mut self, now: SystemTime) {
+        self.failures = self.failures.saturating_add(1);
+        self.last_failure = now
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+    }
+
+    pub fn record_success(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn to_bytes(&self) -> [u8; ENCODED_LEN] {
+        let mut out = [0; ENCODED_LEN];
+        out[..4].copy_from_slice(&self.failures.to_be_bytes());
+        out[4..].copy_from_slice(&self.last_failure.to_be_bytes());
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SrpAuthError> {
+        let bytes: &[u8; ENCODED_LEN] = bytes
+            .try_into()
+            .map_err(|_| SrpAuthError::DecodeError("attempt_tracker"))?;
+        let (failures, last_failure) = bytes.split_at(4);
+        Ok(Self {
+            failures: u32::from_be_bytes(failures.try_into().unwrap()),
+            last_failure: u64::from_be_bytes(last_failure.try_into().unwrap()),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum ThrottleError<E> {
+    Throttled(AttemptDecision),
+    Inner(E),
+}
+
+impl<E> From<E> for ThrottleError<E> {
+    fn from(err: E) -> Self {
+        ThrottleError::Inner(err)
+    }
+}
+
+// Wraps a `PakeClient` with an `AttemptTracker`: `start` is refused while the
+// policy says to wait, any failure in `finish` (the server rejecting our proof
+// or its own proof not verifying) counts against the tracker, and a completed
+// handshake resets it. Earlier errors come from malformed server messages
+// rather than password guesses and are not counted.
+// Persist `tracker()` to keep the count across restarts.
+pub struct ThrottledClient<P> {
+    inner: P,
+    policy: ThrottlePolicy,
+    tracker: RefCell<AttemptTracker>,
+}
+
+impl<P> ThrottledClient<P> {
+    pub fn new(inner: P, policy: ThrottlePolicy, tracker: AttemptTracker) -> Self {
+        Self {
+            inner,
+            policy,
+            tracker: RefCell::new(tracker),
+        }
+    }
+
+    pub fn tracker(&self) -> AttemptTracker {
+        *self.tracker.borrow()
+    }
+}
+
+impl<P: PakeClient> PakeClient for ThrottledClient<P> {
+    type Started = P::Started;
+    type Confirming = P::Confirming;
+    type Key = P::Key;
+    type Error = ThrottleError<P::Error>;
+
+    fn start(
+        &self,
+        username: &[u8],
+        ephemeral: &[u8],
+    ) -> Result<(Vec<u8>, P::Started), Self::Error> {
+        match self
+            .tracker
+            .borrow()
+            .check_with(&self.policy, SystemTime::now())
+        {
+            AttemptDecision::Allowed => {}
+            decision => return Err(ThrottleError::Throttled(decision)),
+        }
+        Ok(self.inner.start(username, ephemeral)?)
+    }
+
+    fn process_challenge(
+        &self,
+        state: P::Started,
+        password: &[u8],
+        challenge: &[u8],
+    ) -> Result<(Vec<u8>, P::Confirming), Self::Error> {
+        Ok(self.inner.process_challenge(state, password, challenge)?)
+    }
+
+    fn finish(&self, state: P::Confirming, server_proof: &[u8]) -> Result<P::Key, Self::Error> {
+        let result = self.inner.finish(state, server_proof);
+        let mut tracker = self.tracker.borrow_mut();
+        match result {
+            Ok(_) => tracker.record_success(),
+            Err(_) => tracker.record_failure(SystemTime::now()),
+        }
+        Ok(result?)
+    }
+}
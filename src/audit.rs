@@ -0,0 +1,137 @@
+use alloc::{sync::Arc, vec::Vec};
+
+use digest::Digest;
+
+use crate::{PakeClient, SessionKey, SrpAuthError, SrpClient, SrpClientVerifier, SrpPakeStart};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditOutcome {
+    Succeeded,
+    Failed(SrpAuthError),
+}
+
+// One completed or abandoned handshake. Nothing in it is secret: the username
+// only appears hashed with the handshake digest, and errors carry nothing but
+// the name of the offending field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEvent {
+    pub group_bits: u64,
+    pub digest: &'static str,
+    pub username_hash: Vec<u8>,
+    pub outcome: AuditOutcome,
+}
+
+pub trait AuditSink {
+    fn record(&self, event: AuditEvent);
+}
+
+impl<S: AuditSink + ?Sized> AuditSink for &S {
+    fn record(&self, event: AuditEvent) {
+        (**self).record(event)
+    }
+}
+
+impl<S: AuditSink + ?Sized> AuditSink for Arc<S> {
+    fn record(&self, event: AuditEvent) {
+        (**self).record(event)
+    }
+}
+
+// Handshake state carried between steps together with the hashed username.
+pub struct Audited<T> {
+    inner: T,
+    username_hash: Vec<u8>,
+}
+
+// Drives an `SrpClient` like the plain `PakeClient` impl and sends exactly
+// one `AuditEvent` per handshake to `sink`: when `finish` succeeds or when
+// any step fails. `digest` is the label recorded for `D`, e.g. "SHA-256".
+pub struct AuditedClient<'a, D: Digest, S> {
+    inner: SrpClient<'a, D>,
+    digest: &'static str,
+    sink: S,
+}
+
+impl<'a, D: Digest, S: AuditSink> AuditedClient<'a, D, S> {
+    pub fn new(inner: SrpClient<'a, D>, digest: &'static str, sink: S) -> Self {
+        Self {
+            inner,
+            digest,
+            sink,
+        }
+    }
+
+    fn record<T>(
+        &self,
+        username_hash: Vec<u8>,
+        result: Result<T, SrpAuthError>,
+    ) -> Result<T, SrpAuthError> {
+        let outcome = match &result {
+            Ok(_) => AuditOutcome::Succeeded,
+            Err(err) => AuditOutcome::Failed(*err),
+        };
+        self.sink.record(AuditEvent {
+            group_bits: self.inner.params.n.bits(),
+            digest: self.digest,
+            username_hash,
+            outcome,
+        });
+        result
+    }
+}
+
+impl<D: Digest, S: AuditSink> PakeClient for AuditedClient<'_, D, S> {
+    type Started = Audited<SrpPakeStart>;
+    type Confirming = Audited<SrpClientVerifier<D>>;
+    type Key = SessionKey<D>;
+    type Error = SrpAuthError;
+
+    fn start(
+        &self,
+        username: &[u8],
+        ephemeral: &[u8],
+    ) -> Result<(Vec<u8>, Self::Started), SrpAuthError> {
+        let username_hash = D::digest(username).to_vec();
+        match self.inner.start(username, ephemeral) {
+            Ok((hello, inner)) => Ok((
+                hello,
+                Audited {
+                    inner,
+                    username_hash,
+                },
+            )),
+            Err(err) => self.record(username_hash, Err(err)),
+        }
+    }
+
+    fn process_challenge(
+        &self,
+        state: Self::Started,
+        password: &[u8],
+        challenge: &[u8],
+    ) -> Result<(Vec<u8>, Self::Confirming), SrpAuthError> {
+        let Audited {
+            inner,
+            username_hash,
+        } = state;
+        match self.inner.process_challenge(inner, password, challenge) {
+            Ok((proof, inner)) => Ok((
+                proof,
+                Audited {
+                    inner,
+                    username_hash,
+                },
+            )),
+            Err(err) => self.record(username_hash, Err(err)),
+        }
+    }
+
+    fn finish(
+        &self,
+        state: Self::Confirming,
+        server_proof: &[u8],
+    ) -> Result<SessionKey<D>, SrpAuthError> {
+        let result = self.inner.finish(state.inner, server_proof);
+        self.record(state.username_hash, result)
+    }
+}
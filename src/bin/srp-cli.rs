@@ -0,0 +1,231 @@
+use std::{
+    env,
+    io::{self, BufRead, IsTerminal, Read, Write},
+    net::TcpStream,
+    process::ExitCode,
+};
+
+use apple_srp_client::{
+    ClientHello, ClientProof, CompatProfile, DigestAlgorithm, DynSrpClient, FRAME_HEADER_LEN,
+    MAX_FRAME_LEN, ServerChallenge, ServerProof, decode_frame, encode_frame, encode_hex,
+    generate_ephemeral,
+};
+use rand_core::OsRng;
+use zeroize::Zeroizing;
+
+const USAGE: &str = "usage:
+  srp-cli verifier <username> [--profile NAME] [--digest NAME]
+  srp-cli handshake <host:port> <username> [--profile NAME] [--digest NAME]
+
+The password is read from the first line of stdin, with a prompt when stdin
+is a terminal.
+
+profiles: apple, rfc5054, pysrp, node, thinbus, openssl
+digests: sha1, sha256, sha512, sha3-256, sha3-512, blake2b";
+
+struct Config {
+    profile: CompatProfile,
+    digest: DigestAlgorithm,
+    args: Vec<String>,
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Config, String> {
+    let mut profile = CompatProfile::Apple;
+    let mut digest = None;
+    let mut positional = Vec::new();
+
+    let mut args = args.peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--profile" => {
+                let name = args.next().ok_or("--profile needs a value")?;
+                profile =
+                    CompatProfile::from_name(&name).ok_or(format!("unknown profile '{name}'"))?;
+            }
+            "--digest" => {
+                let name = args.next().ok_or("--digest needs a value")?;
+                digest = Some(
+                    DigestAlgorithm::from_name(&name).ok_or(format!("unknown digest '{name}'"))?,
+                );
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    Ok(Config {
+        profile,
+        digest: digest.unwrap_or(profile.digest()),
+        args: positional,
+    })
+}
+
+// Reads the header, then exactly the payload it announces, so a length
+// over `MAX_FRAME_LEN` is refused before anything is allocated for it.
+fn read_frame(stream: &mut TcpStream) -> Result<Vec<u8>, String> {
+    let mut header = [0; FRAME_HEADER_LEN];
+    stream.read_exact(&mut header).map_err(|e| e.to_string())?;
+    let len = u32::from_be_bytes(header) as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(format!(
+            "frame of {len} bytes is over the {MAX_FRAME_LEN} byte limit"
+        ));
+    }
+
+    let mut buf = vec![0; FRAME_HEADER_LEN + len];
+    buf[..FRAME_HEADER_LEN].copy_from_slice(&header);
+    stream
+        .read_exact(&mut buf[FRAME_HEADER_LEN..])
+        .map_err(|e| e.to_string())?;
+    match decode_frame(&buf, MAX_FRAME_LEN).map_err(|e| e.to_string())? {
+        Some((payload, _)) => Ok(payload.to_vec()),
+        None => Err("truncated frame".to_owned()),
+    }
+}
+
+fn write_frame(stream: &mut TcpStream, payload: &[u8]) -> Result<(), String> {
+    let frame = encode_frame(payload, MAX_FRAME_LEN).map_err(|e| e.to_string())?;
+    stream.write_all(&frame).map_err(|e| e.to_string())
+}
+
+// Turns terminal echo off until dropped.
+#[cfg(unix)]
+struct EchoOff(libc::termios);
+
+#[cfg(unix)]
+impl EchoOff {
+    fn new() -> Result<Self, String> {
+        let mut termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut termios) } != 0 {
+            return Err(io::Error::last_os_error().to_string());
+        }
+        let mut quiet = termios;
+        quiet.c_lflag &= !libc::ECHO;
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &quiet) } != 0 {
+            return Err(io::Error::last_os_error().to_string());
+        }
+        Ok(Self(termios))
+    }
+}
+
+#[cfg(unix)]
+impl Drop for EchoOff {
+    fn drop(&mut self) {
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.0) };
+    }
+}
+
+// The password never goes through argv, where other users can see it in the
+// process list and the shell keeps it in its history.
+fn read_password() -> Result<Zeroizing<String>, String> {
+    let stdin = io::stdin();
+    let tty = stdin.is_terminal();
+    if tty {
+        eprint!("password: ");
+    }
+    #[cfg(unix)]
+    let echo = if tty { Some(EchoOff::new()?) } else { None };
+
+    // Reserved up front so reading the line does not reallocate and leave
+    // copies behind.
+    let mut password = Zeroizing::new(String::with_capacity(256));
+    let read = stdin.lock().read_line(&mut password);
+    #[cfg(unix)]
+    drop(echo);
+    if tty {
+        eprintln!();
+    }
+    if read.map_err(|e| e.to_string())? == 0 {
+        return Err("no password on stdin".to_owned());
+    }
+    let len = password.trim_end_matches(['\r', '\n']).len();
+    password.truncate(len);
+    Ok(password)
+}
+
+fn verifier(config: &Config) -> Result<(), String> {
+    let [username] = &config.args[..] else {
+        return Err(USAGE.to_owned());
+    };
+    let password = read_password()?;
+    let client = DynSrpClient::with_options(
+        config.profile.group(),
+        config.digest,
+        config.profile.options(),
+    );
+    let registration = client.register(username.as_bytes(), password.as_bytes(), &mut OsRng);
+
+    println!("salt     {}", encode_hex(&registration.salt));
+    println!("verifier {}", encode_hex(&registration.verifier));
+    Ok(())
+}
+
+fn handshake(config: &Config) -> Result<(), String> {
+    let [addr, username] = &config.args[..] else {
+        return Err(USAGE.to_owned());
+    };
+    let password = read_password()?;
+    let client = DynSrpClient::with_options(
+        config.profile.group(),
+        config.digest,
+        config.profile.options(),
+    );
+
+    let a = generate_ephemeral(&mut OsRng);
+    let a_pub = client
+        .compute_public_ephemeral(&*a)
+        .map_err(|e| e.to_string())?;
+
+    let mut stream = TcpStream::connect(addr).map_err(|e| e.to_string())?;
+    let hello = ClientHello {
+        username: username.as_bytes().to_vec(),
+        a_pub,
+    };
+    println!("A        {}", encode_hex(&hello.a_pub));
+    write_frame(&mut stream, &hello.encode().map_err(|e| e.to_string())?)?;
+
+    let challenge =
+        ServerChallenge::decode(&read_frame(&mut stream)?).map_err(|e| e.to_string())?;
+    println!("salt     {}", encode_hex(&challenge.salt));
+    println!("B        {}", encode_hex(&challenge.b_pub));
+
+    let verifier = client
+        .process_reply(
+            &*a,
+            username.as_bytes(),
+            password.as_bytes(),
+            &challenge.salt,
+            &challenge.b_pub,
+        )
+        .map_err(|e| e.to_string())?;
+    println!("M1       {}", encode_hex(verifier.proof()));
+    let proof = ClientProof {
+        m1: verifier.proof().to_vec(),
+    };
+    write_frame(&mut stream, &proof.encode().map_err(|e| e.to_string())?)?;
+
+    let server_proof = ServerProof::decode(&read_frame(&mut stream)?).map_err(|e| e.to_string())?;
+    println!("M2       {}", encode_hex(&server_proof.m2));
+    verifier
+        .verify_server(&server_proof.m2)
+        .map_err(|e| e.to_string())?;
+    println!("server proof verified");
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1);
+    let command = args.next();
+    let result = parse_args(args).and_then(|config| match command.as_deref() {
+        Some("verifier") => verifier(&config),
+        Some("handshake") => handshake(&config),
+        _ => Err(USAGE.to_owned()),
+    });
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
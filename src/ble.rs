@@ -0,0 +1,203 @@
+use alloc::{vec, vec::Vec};
+
+use crate::SrpAuthError;
+
+pub const BLE_OPCODE_SIGNATURE_READ: u8 = 0x01;
+pub const BLE_OPCODE_WRITE: u8 = 0x02;
+pub const BLE_OPCODE_READ: u8 = 0x03;
+pub const BLE_OPCODE_TIMED_WRITE: u8 = 0x04;
+pub const BLE_OPCODE_EXECUTE_WRITE: u8 = 0x05;
+pub const BLE_OPCODE_SERVICE_SIGNATURE_READ: u8 = 0x06;
+pub const BLE_OPCODE_CONFIGURATION: u8 = 0x07;
+pub const BLE_OPCODE_PROTOCOL_CONFIGURATION: u8 = 0x08;
+
+pub const BLE_STATUS_SUCCESS: u8 = 0x00;
+pub const BLE_STATUS_UNSUPPORTED_PDU: u8 = 0x01;
+pub const BLE_STATUS_MAX_PROCEDURES: u8 = 0x02;
+pub const BLE_STATUS_INSUFFICIENT_AUTHORIZATION: u8 = 0x03;
+pub const BLE_STATUS_INVALID_INSTANCE_ID: u8 = 0x04;
+pub const BLE_STATUS_INSUFFICIENT_AUTHENTICATION: u8 = 0x05;
+pub const BLE_STATUS_INVALID_REQUEST: u8 = 0x06;
+
+const CONTROL_CONTINUATION: u8 = 0x80;
+const CONTROL_TYPE_MASK: u8 = 0x0e;
+const CONTROL_REQUEST: u8 = 0x00;
+const CONTROL_RESPONSE: u8 = 0x02;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BleRequest {
+    pub opcode: u8,
+    pub tid: u8,
+    pub iid: u16,
+    pub body: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BleResponse {
+    pub tid: u8,
+    pub status: u8,
+    pub body: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlePdu {
+    Request(BleRequest),
+    Response(BleResponse),
+}
+
+// Splits a PDU into GATT writes of at most `max_len` bytes. The first fragment
+// carries the full header and body length; continuations repeat only the
+// control field and TID. Fails when `max_len` cannot hold the header and at
+// least one body byte, or when the body exceeds its u16 length field.
+fn fragment(
+    control: u8,
+    tid: u8,
+    mut first: Vec<u8>,
+    body: Option<&[u8]>,
+    max_len: usize,
+) -> Result<Vec<Vec<u8>>, SrpAuthError> {
+    if max_len <= first.len() + 2 {
+        return Err(SrpAuthError::IllegalParameter("max_len"));
+    }
+
+    let mut rest: &[u8] = &[];
+    if let Some(body) = body {
+        let len =
+            u16::try_from(body.len()).map_err(|_| SrpAuthError::IllegalParameter("ble_body"))?;
+        first.extend_from_slice(&len.to_le_bytes());
+        let take = body.len().min(max_len - first.len());
+        first.extend_from_slice(&body[..take]);
+        rest = &body[take..];
+    }
+
+    let mut fragments = vec![first];
+    for chunk in rest.chunks(max_len - 2) {
+        let mut fragment = vec![control | CONTROL_CONTINUATION, tid];
+        fragment.extend_from_slice(chunk);
+        fragments.push(fragment);
+    }
+    Ok(fragments)
+}
+
+impl BleRequest {
+    pub fn encode(&self, max_len: usize) -> Result<Vec<Vec<u8>>, SrpAuthError> {
+        let mut header = vec![CONTROL_REQUEST, self.opcode, self.tid];
+        header.extend_from_slice(&self.iid.to_le_bytes());
+        fragment(
+            CONTROL_REQUEST,
+            self.tid,
+            header,
+            self.body.as_deref(),
+            max_len,
+        )
+    }
+}
+
+impl BleResponse {
+    pub fn encode(&self, max_len: usize) -> Result<Vec<Vec<u8>>, SrpAuthError> {
+        let header = vec![CONTROL_RESPONSE, self.tid, self.status];
+        fragment(
+            CONTROL_RESPONSE,
+            self.tid,
+            header,
+            self.body.as_deref(),
+            max_len,
+        )
+    }
+}
+
+impl BlePdu {
+    fn tid(&self) -> u8 {
+        match self {
+            BlePdu::Request(request) => request.tid,
+            BlePdu::Response(response) => response.tid,
+        }
+    }
+
+    fn control(&self) -> u8 {
+        match self {
+            BlePdu::Request(_) => CONTROL_REQUEST,
+            BlePdu::Response(_) => CONTROL_RESPONSE,
+        }
+    }
+
+    fn body_mut(&mut self) -> &mut Vec<u8> {
+        let body = match self {
+            BlePdu::Request(request) => &mut request.body,
+            BlePdu::Response(response) => &mut response.body,
+        };
+        body.get_or_insert_with(Vec::new)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BlePduAssembler {
+    pending: Option<(BlePdu, usize)>,
+}
+
+impl BlePduAssembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Feeds one GATT read or write. Returns the PDU once its last fragment has
+    // arrived; a continuation that does not match the pending PDU, or a body
+    // that overruns its declared length, is a decode error.
+    pub fn push(&mut self, fragment: &[u8]) -> Result<Option<BlePdu>, SrpAuthError> {
+        const ERR: SrpAuthError = SrpAuthError::DecodeError("ble_pdu");
+
+        let (&control, rest) = fragment.split_first().ok_or(ERR)?;
+        if control & !(CONTROL_CONTINUATION | CONTROL_TYPE_MASK) != 0 {
+            return Err(ERR);
+        }
+        let kind = control & CONTROL_TYPE_MASK;
+
+        let (mut pdu, expected, body) = if control & CONTROL_CONTINUATION != 0 {
+            let (&tid, body) = rest.split_first().ok_or(ERR)?;
+            let (pdu, expected) = self.pending.take().ok_or(ERR)?;
+            if pdu.tid() != tid || pdu.control() != kind {
+                return Err(ERR);
+            }
+            (pdu, expected, body)
+        } else {
+            self.pending = None;
+            let (pdu, rest) = match (kind, rest) {
+                (CONTROL_REQUEST, [opcode, tid, iid_lo, iid_hi, rest @ ..]) => (
+                    BlePdu::Request(BleRequest {
+                        opcode: *opcode,
+                        tid: *tid,
+                        iid: u16::from_le_bytes([*iid_lo, *iid_hi]),
+                        body: None,
+                    }),
+                    rest,
+                ),
+                (CONTROL_RESPONSE, [tid, status, rest @ ..]) => (
+                    BlePdu::Response(BleResponse {
+                        tid: *tid,
+                        status: *status,
+                        body: None,
+                    }),
+                    rest,
+                ),
+                _ => return Err(ERR),
+            };
+            match rest.split_first_chunk::<2>() {
+                None if rest.is_empty() => return Ok(Some(pdu)),
+                None => return Err(ERR),
+                Some((len, body)) => (pdu, u16::from_le_bytes(*len) as usize, body),
+            }
+        };
+
+        let buf = pdu.body_mut();
+        if buf.len() + body.len() > expected {
+            return Err(ERR);
+        }
+        buf.extend_from_slice(body);
+        if buf.len() == expected {
+            Ok(Some(pdu))
+        } else {
+            self.pending = Some((pdu, expected));
+            Ok(None)
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use std::{
+    ffi::{CStr, c_char},
+    ptr, slice,
+};
+
+use crate::{CompatProfile, DigestAlgorithm, DynSrpClient, DynSrpClientVerifier, SrpAuthError};
+
+// Flat handle-based C API for FFIs that cannot marshal slices or structs
+// (Dart, LuaJIT, ...); `include/apple_srp_client.h` declares it. Every byte
+// result is written into a caller-owned buffer of SRP_BUFFER_LEN bytes and the
+// return value is the number of bytes written, or a negative SRP_ERR_* code.
+// Input pointers may be null when their length is zero; beyond that, callers
+// must pass valid buffers and live handles, which is the only safety contract.
+pub const SRP_BUFFER_LEN: usize = 512;
+
+pub const SRP_OK: i32 = 0;
+pub const SRP_ERR_NULL_POINTER: i32 = -1;
+pub const SRP_ERR_ILLEGAL_PARAMETER: i32 = -2;
+pub const SRP_ERR_BAD_RECORD_MAC: i32 = -3;
+pub const SRP_ERR_DECODE: i32 = -4;
+pub const SRP_ERR_UNKNOWN_NAME: i32 = -5;
+pub const SRP_ERR_BUFFER_TOO_SMALL: i32 = -6;
+
+pub struct SrpClientHandle {
+    inner: DynSrpClient<'static>,
+}
+
+pub struct SrpVerifierHandle {
+    inner: DynSrpClientVerifier,
+}
+
+fn error_code(err: SrpAuthError) -> i32 {
+    match err {
+        SrpAuthError::IllegalParameter(_) => SRP_ERR_ILLEGAL_PARAMETER,
+        SrpAuthError::BadRecordMac(_) => SRP_ERR_BAD_RECORD_MAC,
+        SrpAuthError::DecodeError(_) => SRP_ERR_DECODE,
+    }
+}
+
+unsafe fn input<'a>(data: *const u8, len: usize) -> Result<&'a [u8], i32> {
+    match (data.is_null(), len) {
+        (_, 0) => Ok(&[]),
+        (true, _) => Err(SRP_ERR_NULL_POINTER),
+        (false, len) => Ok(unsafe { slice::from_raw_parts(data, len) }),
+    }
+}
+
+unsafe fn name<'a>(name: *const c_char) -> Result<Option<&'a str>, i32> {
+    if name.is_null() {
+        return Ok(None);
+    }
+    let name = unsafe { CStr::from_ptr(name) };
+    name.to_str().map(Some).map_err(|_| SRP_ERR_UNKNOWN_NAME)
+}
+
+unsafe fn output(out: *mut u8, bytes: &[u8]) -> i32 {
+    if out.is_null() {
+        return SRP_ERR_NULL_POINTER;
+    }
+    if bytes.len() > SRP_BUFFER_LEN {
+        return SRP_ERR_BUFFER_TOO_SMALL;
+    }
+    unsafe { ptr::copy_nonoverlapping(bytes.as_ptr(), out, bytes.len()) };
+    bytes.len() as i32
+}
+
+fn code(result: Result<i32, i32>) -> i32 {
+    result.unwrap_or_else(|code| code)
+}
+
+// A null profile or digest selects the default profile or that profile's
+// digest. On success `*out` owns a client that must go to srp_client_free.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn srp_client_new(
+    profile: *const c_char,
+    digest: *const c_char,
+    out: *mut *mut SrpClientHandle,
+) -> i32 {
+    code((|| {
+        if out.is_null() {
+            return Err(SRP_ERR_NULL_POINTER);
+        }
+        let profile = match unsafe { name(profile) }? {
+            Some(name) => CompatProfile::from_name(name).ok_or(SRP_ERR_UNKNOWN_NAME)?,
+            None => CompatProfile::default(),
+        };
+        let algorithm = match unsafe { name(digest) }? {
+            Some(name) => DigestAlgorithm::from_name(name).ok_or(SRP_ERR_UNKNOWN_NAME)?,
+            None => profile.digest(),
+        };
+        let client = SrpClientHandle {
+            inner: DynSrpClient::with_options(profile.group(), algorithm, profile.options()),
+        };
+        unsafe { *out = Box::into_raw(Box::new(client)) };
+        Ok(SRP_OK)
+    })())
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn srp_client_free(client: *mut SrpClientHandle) {
+    if !client.is_null() {
+        drop(unsafe { Box::from_raw(client) });
+    }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn srp_compute_verifier(
+    client: *const SrpClientHandle,
+    username: *const u8,
+    username_len: usize,
+    password: *const u8,
+    password_len: usize,
+    salt: *const u8,
+    salt_len: usize,
+    out: *mut u8,
+) -> i32 {
+    code((|| {
+        let client = unsafe { client.as_ref() }.ok_or(SRP_ERR_NULL_POINTER)?;
+        let username = unsafe { input(username, username_len) }?;
+        let password = unsafe { input(password, password_len) }?;
+        let salt = unsafe { input(salt, salt_len) }?;
+        let verifier = client.inner.compute_verifier(username, password, salt);
+        Ok(unsafe { output(out, &verifier) })
+    })())
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn srp_compute_public_ephemeral(
+    client: *const SrpClientHandle,
+    a: *const u8,
+    a_len: usize,
+    out: *mut u8,
+) -> i32 {
+    code((|| {
+        let client = unsafe { client.as_ref() }.ok_or(SRP_ERR_NULL_POINTER)?;
+        let a = unsafe { input(a, a_len) }?;
+        let a_pub = client
+            .inner
+            .compute_public_ephemeral(a)
+            .map_err(error_code)?;
+        Ok(unsafe { output(out, &a_pub) })
+    })())
+}
+
+// On success `*out` owns a verifier that must go to either
+// srp_verifier_verify_server or srp_verifier_free.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn srp_process_reply(
+    client: *const SrpClientHandle,
+    a: *const u8,
+    a_len: usize,
+    username: *const u8,
+    username_len: usize,
+    password: *const u8,
+    password_len: usize,
+    salt: *const u8,
+    salt_len: usize,
+    b_pub: *const u8,
+    b_pub_len: usize,
+    out: *mut *mut SrpVerifierHandle,
+) -> i32 {
+    code((|| {
+        let client = unsafe { client.as_ref() }.ok_or(SRP_ERR_NULL_POINTER)?;
+        if out.is_null() {
+            return Err(SRP_ERR_NULL_POINTER);
+        }
+        let a = unsafe { input(a, a_len) }?;
+        let username = unsafe { input(username, username_len) }?;
+        let password = unsafe { input(password, password_len) }?;
+        let salt = unsafe { input(salt, salt_len) }?;
+        let b_pub = unsafe { input(b_pub, b_pub_len) }?;
+        let inner = client
+            .inner
+            .process_reply(a, username, password, salt, b_pub)
+            .map_err(error_code)?;
+        unsafe { *out = Box::into_raw(Box::new(SrpVerifierHandle { inner })) };
+        Ok(SRP_OK)
+    })())
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn srp_verifier_proof(
+    verifier: *const SrpVerifierHandle,
+    out: *mut u8,
+) -> i32 {
+    match unsafe { verifier.as_ref() } {
+        Some(verifier) => unsafe { output(out, verifier.inner.proof()) },
+        None => SRP_ERR_NULL_POINTER,
+    }
+}
+
+// Writes the session key. Always consumes `verifier`, whether or not the
+// server proof is accepted.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn srp_verifier_verify_server(
+    verifier: *mut SrpVerifierHandle,
+    m2: *const u8,
+    m2_len: usize,
+    out: *mut u8,
+) -> i32 {
+    if verifier.is_null() {
+        return SRP_ERR_NULL_POINTER;
+    }
+    let verifier = unsafe { Box::from_raw(verifier) };
+    code((|| {
+        let m2 = unsafe { input(m2, m2_len) }?;
+        let key = verifier.inner.verify_server(m2).map_err(error_code)?;
+        Ok(unsafe { output(out, key.as_bytes()) })
+    })())
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn srp_verifier_free(verifier: *mut SrpVerifierHandle) {
+    if !verifier.is_null() {
+        drop(unsafe { Box::from_raw(verifier) });
+    }
+}
@@ -0,0 +1,181 @@
+use std::time::{Duration, SystemTime};
+
+use digest::Digest;
+
+use crate::Credentials;
+
+// Persistence for derived credentials. x depends on the client's options, so
+// a store, like a cache, should hold credentials for one configuration.
+pub trait CredentialStore<D: Digest> {
+    type Error;
+
+    // None when nothing is stored for `username` or it was derived for
+    // another salt.
+    fn load_credentials(
+        &self,
+        username: &[u8],
+        salt: &[u8],
+    ) -> Result<Option<Credentials<D>>, Self::Error>;
+
+    fn save_credentials(
+        &mut self,
+        username: &[u8],
+        credentials: &Credentials<D>,
+    ) -> Result<(), Self::Error>;
+
+    fn remove_credentials(&mut self, username: &[u8]) -> Result<(), Self::Error>;
+}
+
+struct Entry<D: Digest> {
+    username: Vec<u8>,
+    salt: Vec<u8>,
+    credentials: Credentials<D>,
+    expires_at: SystemTime,
+    last_used: u64,
+}
+
+// Derived credentials for recently used accounts, so reconnecting to the same
+// server skips the password hashing. Entries live for `ttl` and the least
+// recently used one is evicted once `capacity` is reached. An entry only
+// answers for the salt it was derived with, and x depends on the client's
+// options, so keep one cache per client configuration.
+pub struct CredentialCache<D: Digest> {
+    capacity: usize,
+    ttl: Duration,
+    entries: Vec<Entry<D>>,
+    clock: u64,
+}
+
+impl<D: Digest> CredentialCache<D> {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            entries: Vec::with_capacity(capacity),
+            clock: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn position(&mut self, username: &[u8], salt: &[u8], now: SystemTime) -> Option<usize> {
+        let index = self
+            .entries
+            .iter()
+            .position(|entry| entry.username == username)?;
+        let entry = &self.entries[index];
+        if entry.salt != salt || entry.expires_at <= now {
+            self.entries.swap_remove(index);
+            return None;
+        }
+        self.clock += 1;
+        self.entries[index].last_used = self.clock;
+        Some(index)
+    }
+
+    // A stale entry for `username`, whether expired or for another salt, is
+    // dropped rather than returned.
+    pub fn get(
+        &mut self,
+        username: &[u8],
+        salt: &[u8],
+        now: SystemTime,
+    ) -> Option<&Credentials<D>> {
+        let index = self.position(username, salt, now)?;
+        Some(&self.entries[index].credentials)
+    }
+
+    pub fn insert(
+        &mut self,
+        username: &[u8],
+        salt: &[u8],
+        credentials: Credentials<D>,
+        now: SystemTime,
+    ) {
+        self.remove(username);
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() >= self.capacity {
+            self.purge_expired(now);
+        }
+        if self.entries.len() >= self.capacity {
+            let oldest = (0..self.entries.len())
+                .min_by_key(|&index| self.entries[index].last_used)
+                .expect("a full cache has entries");
+            self.entries.swap_remove(oldest);
+        }
+        self.clock += 1;
+        self.entries.push(Entry {
+            username: username.to_vec(),
+            salt: salt.to_vec(),
+            credentials,
+            expires_at: now + self.ttl,
+            last_used: self.clock,
+        });
+    }
+
+    // `derive` runs only on a miss, e.g.
+    // `|| client.credentials(username, password, salt)`. None only when the
+    // cache cannot hold anything: a zero capacity or ttl.
+    pub fn get_or_insert_with<F: FnOnce() -> Credentials<D>>(
+        &mut self,
+        username: &[u8],
+        salt: &[u8],
+        now: SystemTime,
+        derive: F,
+    ) -> Option<&Credentials<D>> {
+        if self.position(username, salt, now).is_none() {
+            self.insert(username, salt, derive(), now);
+        }
+        self.get(username, salt, now)
+    }
+
+    // As `get_or_insert_with`, with `store` consulted on a miss before
+    // `derive`, and anything newly derived saved to it.
+    pub fn get_or_load<S, F>(
+        &mut self,
+        store: &mut S,
+        username: &[u8],
+        salt: &[u8],
+        now: SystemTime,
+        derive: F,
+    ) -> Result<Option<&Credentials<D>>, S::Error>
+    where
+        S: CredentialStore<D> + ?Sized,
+        F: FnOnce() -> Credentials<D>,
+    {
+        if self.position(username, salt, now).is_none() {
+            let credentials = match store.load_credentials(username, salt)? {
+                Some(credentials) => credentials,
+                None => {
+                    let credentials = derive();
+                    store.save_credentials(username, &credentials)?;
+                    credentials
+                }
+            };
+            self.insert(username, salt, credentials, now);
+        }
+        Ok(self.get(username, salt, now))
+    }
+
+    pub fn remove(&mut self, username: &[u8]) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|entry| entry.username != username);
+        self.entries.len() != before
+    }
+
+    pub fn purge_expired(&mut self, now: SystemTime) {
+        self.entries.retain(|entry| entry.expires_at > now);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
@@ -0,0 +1,14 @@
+use subtle::{Choice, ConstantTimeEq};
+
+// Byte-slice equality whose timing depends only on the longer input's length.
+// A length mismatch does not return early, so a wrong-length proof takes as
+// long to reject as a wrong proof of the right length.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    let mut equal: Choice = (a.len() as u64).ct_eq(&(b.len() as u64));
+    for i in 0..a.len().max(b.len()) {
+        let x = a.get(i).copied().unwrap_or(0);
+        let y = b.get(i).copied().unwrap_or(0);
+        equal &= x.ct_eq(&y);
+    }
+    equal.into()
+}
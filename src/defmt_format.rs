@@ -0,0 +1,111 @@
+use ::defmt::{Format, Formatter, write};
+
+use crate::{
+    ClientHello, ClientProof, EapSrpMessage, EapSrpPacket, HapError, ServerChallenge, ServerProof,
+    SrpAuthError,
+};
+
+// `defmt::Format` for RTT logs on embedded targets. Public values are printed
+// as hex; proofs are reduced to their length because M1 together with the
+// public values is enough to mount an offline guess on the password.
+
+impl Format for SrpAuthError {
+    fn format(&self, fmt: Formatter<'_>) {
+        match self {
+            SrpAuthError::IllegalParameter(param) => {
+                write!(fmt, "illegal_parameter: bad '{=str}' value", param)
+            }
+            SrpAuthError::BadRecordMac(param) => {
+                write!(fmt, "bad_record_mac: incorrect '{=str}' proof", param)
+            }
+            SrpAuthError::DecodeError(param) => {
+                write!(fmt, "decode_error: malformed '{=str}' message", param)
+            }
+        }
+    }
+}
+
+impl Format for ClientHello {
+    fn format(&self, fmt: Formatter<'_>) {
+        write!(
+            fmt,
+            "ClientHello {{ username: {=[u8]:a}, a_pub: {=[u8]:02x} }}",
+            self.username, self.a_pub
+        )
+    }
+}
+
+impl Format for ServerChallenge {
+    fn format(&self, fmt: Formatter<'_>) {
+        write!(
+            fmt,
+            "ServerChallenge {{ salt: {=[u8]:02x}, b_pub: {=[u8]:02x} }}",
+            self.salt, self.b_pub
+        )
+    }
+}
+
+impl Format for ClientProof {
+    fn format(&self, fmt: Formatter<'_>) {
+        write!(fmt, "ClientProof {{ m1: <{=usize} bytes> }}", self.m1.len())
+    }
+}
+
+impl Format for ServerProof {
+    fn format(&self, fmt: Formatter<'_>) {
+        write!(fmt, "ServerProof {{ m2: <{=usize} bytes> }}", self.m2.len())
+    }
+}
+
+impl Format for EapSrpMessage {
+    fn format(&self, fmt: Formatter<'_>) {
+        match self {
+            EapSrpMessage::ChallengeRequest { name, salt, .. } => write!(
+                fmt,
+                "ChallengeRequest {{ name: {=[u8]:a}, salt: {=[u8]:02x} }}",
+                name, salt
+            ),
+            EapSrpMessage::ChallengeResponse { a_pub } => {
+                write!(fmt, "ChallengeResponse {{ a_pub: {=[u8]:02x} }}", a_pub)
+            }
+            EapSrpMessage::KeyRequest { b_pub } => {
+                write!(fmt, "KeyRequest {{ b_pub: {=[u8]:02x} }}", b_pub)
+            }
+            EapSrpMessage::KeyResponse { m1 } => {
+                write!(fmt, "KeyResponse {{ m1: <{=usize} bytes> }}", m1.len())
+            }
+            EapSrpMessage::ValidatorRequest { flags, m2 } => write!(
+                fmt,
+                "ValidatorRequest {{ flags: {=u32:#x}, m2: <{=usize} bytes> }}",
+                flags,
+                m2.len()
+            ),
+            EapSrpMessage::ValidatorResponse => write!(fmt, "ValidatorResponse"),
+        }
+    }
+}
+
+impl Format for EapSrpPacket {
+    fn format(&self, fmt: Formatter<'_>) {
+        write!(
+            fmt,
+            "EapSrpPacket {{ identifier: {=u8}, message: {} }}",
+            self.identifier, self.message
+        )
+    }
+}
+
+impl Format for HapError {
+    fn format(&self, fmt: Formatter<'_>) {
+        match self {
+            HapError::Unknown => write!(fmt, "HapError::Unknown"),
+            HapError::Authentication => write!(fmt, "HapError::Authentication"),
+            HapError::Backoff => write!(fmt, "HapError::Backoff"),
+            HapError::MaxPeers => write!(fmt, "HapError::MaxPeers"),
+            HapError::MaxTries => write!(fmt, "HapError::MaxTries"),
+            HapError::Unavailable => write!(fmt, "HapError::Unavailable"),
+            HapError::Busy => write!(fmt, "HapError::Busy"),
+            HapError::Other(code) => write!(fmt, "HapError::Other({=u8:#x})", code),
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+use core::net::IpAddr;
+
+use crate::SrpAuthError;
+
+pub const HAP_SERVICE_TYPE: &str = "_hap._tcp.local.";
+
+pub const STATUS_FLAG_NOT_PAIRED: u8 = 0x01;
+pub const STATUS_FLAG_NOT_CONFIGURED: u8 = 0x02;
+pub const STATUS_FLAG_PROBLEM: u8 = 0x04;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HapAccessoryRecord {
+    pub name: String,
+    pub id: String,
+    pub model: String,
+    pub category: u16,
+    pub status_flags: u8,
+    pub config_number: u32,
+    pub state_number: u32,
+    pub feature_flags: u8,
+    pub protocol_version: String,
+    pub addresses: Vec<IpAddr>,
+    pub port: u16,
+}
+
+impl HapAccessoryRecord {
+    // Builds a record from the instance name and the `_hap._tcp` TXT keys;
+    // `id`, `md`, `ci`, `sf` and `c#` are required, the rest default as the
+    // HAP specification allows.
+    pub fn from_txt<'a>(
+        name: &str,
+        txt: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Result<Self, SrpAuthError> {
+        const ERR: SrpAuthError = SrpAuthError::DecodeError("hap_txt");
+
+        let mut record = Self {
+            name: name.to_owned(),
+            id: String::new(),
+            model: String::new(),
+            category: 0,
+            status_flags: 0,
+            config_number: 0,
+            state_number: 1,
+            feature_flags: 0,
+            protocol_version: String::from("1.0"),
+            addresses: Vec::new(),
+            port: 0,
+        };
+        let mut required = 0u8;
+        for (key, value) in txt {
+            match key {
+                "id" => {
+                    record.id = value.to_owned();
+                    required |= 0x01;
+                }
+                "md" => {
+                    record.model = value.to_owned();
+                    required |= 0x02;
+                }
+                "ci" => {
+                    record.category = value.parse().map_err(|_| ERR)?;
+                    required |= 0x04;
+                }
+                "sf" => {
+                    record.status_flags = value.parse().map_err(|_| ERR)?;
+                    required |= 0x08;
+                }
+                "c#" => {
+                    record.config_number = value.parse().map_err(|_| ERR)?;
+                    required |= 0x10;
+                }
+                "s#" => record.state_number = value.parse().map_err(|_| ERR)?,
+                "ff" => record.feature_flags = value.parse().map_err(|_| ERR)?,
+                "pv" => record.protocol_version = value.to_owned(),
+                _ => {}
+            }
+        }
+        if required != 0x1f {
+            return Err(ERR);
+        }
+        Ok(record)
+    }
+
+    pub fn is_paired(&self) -> bool {
+        self.status_flags & STATUS_FLAG_NOT_PAIRED == 0
+    }
+}
+
+#[cfg(feature = "discovery")]
+pub use browse::browse_hap_accessories;
+
+#[cfg(feature = "discovery")]
+mod browse {
+    use std::time::{Duration, Instant};
+
+    use mdns_sd::{ServiceDaemon, ServiceEvent};
+
+    use super::{HAP_SERVICE_TYPE, HapAccessoryRecord};
+
+    // Browses for `timeout` and returns every accessory that resolved, with
+    // the address and port to open the Pair-Setup connection to. Instances
+    // with unparseable TXT records are skipped.
+    pub fn browse_hap_accessories(timeout: Duration) -> mdns_sd::Result<Vec<HapAccessoryRecord>> {
+        let daemon = ServiceDaemon::new()?;
+        let events = daemon.browse(HAP_SERVICE_TYPE)?;
+        let deadline = Instant::now() + timeout;
+
+        let mut records: Vec<HapAccessoryRecord> = Vec::new();
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            let Ok(event) = events.recv_timeout(remaining) else {
+                break;
+            };
+            let ServiceEvent::ServiceResolved(info) = event else {
+                continue;
+            };
+
+            let name = info
+                .get_fullname()
+                .strip_suffix(HAP_SERVICE_TYPE)
+                .map_or(info.get_fullname(), |name| name.trim_end_matches('.'));
+            let txt = info
+                .get_properties()
+                .iter()
+                .map(|property| (property.key(), property.val_str()));
+            let Ok(mut record) = HapAccessoryRecord::from_txt(name, txt) else {
+                continue;
+            };
+            record.addresses = info.get_addresses().iter().copied().collect();
+            record.addresses.sort();
+            record.port = info.get_port();
+
+            records.retain(|known| known.id != record.id);
+            records.push(record);
+        }
+
+        let _ = daemon.shutdown();
+        Ok(records)
+    }
+}
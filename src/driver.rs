@@ -0,0 +1,69 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::PakeClient;
+
+// The link a handshake runs over: a BLE characteristic, a UART, a socket. The
+// driver only borrows it, so firmware keeps ownership and can reuse it for
+// the encrypted session that follows. Nothing here needs std or a clock;
+// timeouts belong to the executor (e.g. embassy's `with_timeout`).
+pub trait PakeTransport {
+    type Error;
+
+    fn send(&mut self, message: &[u8]) -> impl Future<Output = Result<(), Self::Error>>;
+
+    fn receive(&mut self) -> impl Future<Output = Result<Vec<u8>, Self::Error>>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DriveError<P, T> {
+    Pake(P),
+    Transport(T),
+}
+
+impl<P: fmt::Display, T: fmt::Display> fmt::Display for DriveError<P, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DriveError::Pake(err) => write!(f, "handshake failed: {err}"),
+            DriveError::Transport(err) => write!(f, "transport failed: {err}"),
+        }
+    }
+}
+
+impl<P: fmt::Debug + fmt::Display, T: fmt::Debug + fmt::Display> core::error::Error
+    for DriveError<P, T>
+{
+}
+
+// Runs hello, challenge, proof and server proof over `transport`. A failed
+// send or receive aborts the handshake; the caller starts over with a fresh
+// ephemeral.
+pub async fn drive_pake<P: PakeClient, T: PakeTransport>(
+    client: &P,
+    transport: &mut T,
+    username: &[u8],
+    password: &[u8],
+    ephemeral: &[u8],
+) -> Result<P::Key, DriveError<P::Error, T::Error>> {
+    let (hello, started) = client
+        .start(username, ephemeral)
+        .map_err(DriveError::Pake)?;
+    transport
+        .send(&hello)
+        .await
+        .map_err(DriveError::Transport)?;
+
+    let challenge = transport.receive().await.map_err(DriveError::Transport)?;
+    let (proof, confirming) = client
+        .process_challenge(started, password, &challenge)
+        .map_err(DriveError::Pake)?;
+    transport
+        .send(&proof)
+        .await
+        .map_err(DriveError::Transport)?;
+
+    let server_proof = transport.receive().await.map_err(DriveError::Transport)?;
+    client
+        .finish(confirming, &server_proof)
+        .map_err(DriveError::Pake)
+}
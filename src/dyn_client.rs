@@ -0,0 +1,333 @@
+use alloc::{borrow::Cow, vec::Vec};
+
+use blake2::Blake2b512;
+use digest::Digest;
+use rand_core::CryptoRngCore;
+use sha1::Sha1;
+use sha2::{Sha256, Sha512};
+use sha3::{Sha3_256, Sha3_512};
+
+#[cfg(feature = "unsafe-debug")]
+use crate::HandshakeIntermediates;
+use crate::{
+    KeyConfirmation, RegistrationData, SESSION_ID_LEN, SessionKey, SrpAuthError, SrpClient,
+    SrpClientVerifier, SrpGroup, SrpOptions,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestAlgorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+    Sha3_256,
+    Sha3_512,
+    Blake2b512,
+}
+
+impl DigestAlgorithm {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().replace(['-', '_'], "").as_str() {
+            "sha1" => Some(Self::Sha1),
+            "sha256" => Some(Self::Sha256),
+            "sha512" => Some(Self::Sha512),
+            "sha3256" => Some(Self::Sha3_256),
+            "sha3512" => Some(Self::Sha3_512),
+            "blake2b" | "blake2b512" => Some(Self::Blake2b512),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Sha1 => "SHA-1",
+            Self::Sha256 => "SHA-256",
+            Self::Sha512 => "SHA-512",
+            Self::Sha3_256 => "SHA3-256",
+            Self::Sha3_512 => "SHA3-512",
+            Self::Blake2b512 => "BLAKE2b-512",
+        }
+    }
+
+    pub fn output_size(&self) -> usize {
+        match self {
+            Self::Sha1 => 20,
+            Self::Sha256 => 32,
+            Self::Sha512 => 64,
+            Self::Sha3_256 => 32,
+            Self::Sha3_512 => 64,
+            Self::Blake2b512 => 64,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub enum DynSrpClientVerifier {
+    Sha1(SrpClientVerifier<Sha1>),
+    Sha256(SrpClientVerifier<Sha256>),
+    Sha512(SrpClientVerifier<Sha512>),
+    Sha3_256(SrpClientVerifier<Sha3_256>),
+    Sha3_512(SrpClientVerifier<Sha3_512>),
+    Blake2b512(SrpClientVerifier<Blake2b512>),
+}
+
+pub enum DynSessionKey {
+    Sha1(SessionKey<Sha1>),
+    Sha256(SessionKey<Sha256>),
+    Sha512(SessionKey<Sha512>),
+    Sha3_256(SessionKey<Sha3_256>),
+    Sha3_512(SessionKey<Sha3_512>),
+    Blake2b512(SessionKey<Blake2b512>),
+}
+
+#[derive(Clone)]
+pub struct DynSrpClient<'a> {
+    params: Cow<'a, SrpGroup>,
+    options: SrpOptions,
+    algorithm: DigestAlgorithm,
+    channel_binding: Option<&'a [u8]>,
+}
+
+// Runs `$body` with `$d` bound to the digest type for `$algorithm`. The
+// second form also binds `$variant` to the matching constructor of `$wrap`,
+// one of the enums above, for bodies that return a typed value.
+macro_rules! dispatch {
+    ($algorithm:expr, $d:ident => $body:expr) => {
+        dispatch!($algorithm, $d, _variant: DigestAlgorithm => $body)
+    };
+    ($algorithm:expr, $d:ident, $variant:ident: $wrap:ident => $body:expr) => {
+        match $algorithm {
+            DigestAlgorithm::Sha1 => {
+                type $d = Sha1;
+                let $variant = $wrap::Sha1;
+                $body
+            }
+            DigestAlgorithm::Sha256 => {
+                type $d = Sha256;
+                let $variant = $wrap::Sha256;
+                $body
+            }
+            DigestAlgorithm::Sha512 => {
+                type $d = Sha512;
+                let $variant = $wrap::Sha512;
+                $body
+            }
+            DigestAlgorithm::Sha3_256 => {
+                type $d = Sha3_256;
+                let $variant = $wrap::Sha3_256;
+                $body
+            }
+            DigestAlgorithm::Sha3_512 => {
+                type $d = Sha3_512;
+                let $variant = $wrap::Sha3_512;
+                $body
+            }
+            DigestAlgorithm::Blake2b512 => {
+                type $d = Blake2b512;
+                let $variant = $wrap::Blake2b512;
+                $body
+            }
+        }
+    };
+}
+
+impl DynSrpClient<'static> {
+    pub fn with_owned_group(
+        params: SrpGroup,
+        algorithm: DigestAlgorithm,
+        options: SrpOptions,
+    ) -> Self {
+        Self {
+            params: Cow::Owned(params),
+            options,
+            algorithm,
+            channel_binding: None,
+        }
+    }
+}
+
+impl<'a> DynSrpClient<'a> {
+    pub fn new(params: &'a SrpGroup, algorithm: DigestAlgorithm) -> Self {
+        Self::with_options(params, algorithm, SrpOptions::default())
+    }
+
+    pub fn with_options(
+        params: &'a SrpGroup,
+        algorithm: DigestAlgorithm,
+        options: SrpOptions,
+    ) -> Self {
+        Self {
+            params: Cow::Borrowed(params),
+            options,
+            algorithm,
+            channel_binding: None,
+        }
+    }
+
+    pub fn with_channel_binding<'b>(self, binding: &'b [u8]) -> DynSrpClient<'b>
+    where
+        'a: 'b,
+    {
+        DynSrpClient {
+            channel_binding: Some(binding),
+            ..self
+        }
+    }
+
+    pub fn algorithm(&self) -> DigestAlgorithm {
+        self.algorithm
+    }
+
+    fn client<D: Digest>(&self) -> SrpClient<'_, D> {
+        let client = SrpClient::with_options(&self.params, self.options);
+        match self.channel_binding {
+            Some(binding) => client.with_channel_binding(binding),
+            None => client,
+        }
+    }
+
+    pub fn compute_verifier(&self, username: &[u8], password: &[u8], salt: &[u8]) -> Vec<u8> {
+        dispatch!(self.algorithm, D => {
+            self.client::<D>()
+                .compute_verifier(username, password, salt)
+        })
+    }
+
+    pub fn verify_password_against_verifier(
+        &self,
+        username: &[u8],
+        password: &[u8],
+        salt: &[u8],
+        verifier: &[u8],
+    ) -> bool {
+        dispatch!(self.algorithm, D => {
+            self.client::<D>()
+                .verify_password_against_verifier(username, password, salt, verifier)
+        })
+    }
+
+    pub fn register<R: CryptoRngCore + ?Sized>(
+        &self,
+        username: &[u8],
+        password: &[u8],
+        rng: &mut R,
+    ) -> RegistrationData {
+        dispatch!(self.algorithm, D => {
+            self.client::<D>().register(username, password, rng)
+        })
+    }
+
+    pub fn compute_public_ephemeral(&self, a: &[u8]) -> Result<Vec<u8>, SrpAuthError> {
+        dispatch!(self.algorithm, D => {
+            self.client::<D>().compute_public_ephemeral(a)
+        })
+    }
+
+    pub fn process_reply(
+        &self,
+        a: &[u8],
+        username: &[u8],
+        password: &[u8],
+        salt: &[u8],
+        b_pub: &[u8],
+    ) -> Result<DynSrpClientVerifier, SrpAuthError> {
+        dispatch!(self.algorithm, D, verifier: DynSrpClientVerifier => {
+            Ok(verifier(
+                self.client::<D>()
+                    .process_reply(a, username, password, salt, b_pub)?,
+            ))
+        })
+    }
+}
+
+impl DynSrpClientVerifier {
+    pub fn algorithm(&self) -> DigestAlgorithm {
+        match self {
+            Self::Sha1(_) => DigestAlgorithm::Sha1,
+            Self::Sha256(_) => DigestAlgorithm::Sha256,
+            Self::Sha512(_) => DigestAlgorithm::Sha512,
+            Self::Sha3_256(_) => DigestAlgorithm::Sha3_256,
+            Self::Sha3_512(_) => DigestAlgorithm::Sha3_512,
+            Self::Blake2b512(_) => DigestAlgorithm::Blake2b512,
+        }
+    }
+
+    pub fn proof(&self) -> &[u8] {
+        match self {
+            Self::Sha1(verifier) => verifier.proof(),
+            Self::Sha256(verifier) => verifier.proof(),
+            Self::Sha512(verifier) => verifier.proof(),
+            Self::Sha3_256(verifier) => verifier.proof(),
+            Self::Sha3_512(verifier) => verifier.proof(),
+            Self::Blake2b512(verifier) => verifier.proof(),
+        }
+    }
+
+    #[cfg(feature = "unsafe-debug")]
+    pub fn intermediates(&self) -> Option<&HandshakeIntermediates> {
+        match self {
+            Self::Sha1(verifier) => verifier.intermediates(),
+            Self::Sha256(verifier) => verifier.intermediates(),
+            Self::Sha512(verifier) => verifier.intermediates(),
+            Self::Sha3_256(verifier) => verifier.intermediates(),
+            Self::Sha3_512(verifier) => verifier.intermediates(),
+            Self::Blake2b512(verifier) => verifier.intermediates(),
+        }
+    }
+
+    pub fn verify_server(self, reply: &[u8]) -> Result<DynSessionKey, SrpAuthError> {
+        Ok(match self {
+            Self::Sha1(verifier) => DynSessionKey::Sha1(verifier.verify_server(reply)?),
+            Self::Sha256(verifier) => DynSessionKey::Sha256(verifier.verify_server(reply)?),
+            Self::Sha512(verifier) => DynSessionKey::Sha512(verifier.verify_server(reply)?),
+            Self::Sha3_256(verifier) => DynSessionKey::Sha3_256(verifier.verify_server(reply)?),
+            Self::Sha3_512(verifier) => DynSessionKey::Sha3_512(verifier.verify_server(reply)?),
+            Self::Blake2b512(verifier) => DynSessionKey::Blake2b512(verifier.verify_server(reply)?),
+        })
+    }
+}
+
+impl DynSessionKey {
+    pub fn algorithm(&self) -> DigestAlgorithm {
+        match self {
+            Self::Sha1(_) => DigestAlgorithm::Sha1,
+            Self::Sha256(_) => DigestAlgorithm::Sha256,
+            Self::Sha512(_) => DigestAlgorithm::Sha512,
+            Self::Sha3_256(_) => DigestAlgorithm::Sha3_256,
+            Self::Sha3_512(_) => DigestAlgorithm::Sha3_512,
+            Self::Blake2b512(_) => DigestAlgorithm::Blake2b512,
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Sha1(key) => key.as_bytes(),
+            Self::Sha256(key) => key.as_bytes(),
+            Self::Sha512(key) => key.as_bytes(),
+            Self::Sha3_256(key) => key.as_bytes(),
+            Self::Sha3_512(key) => key.as_bytes(),
+            Self::Blake2b512(key) => key.as_bytes(),
+        }
+    }
+
+    pub fn session_id(&self) -> [u8; SESSION_ID_LEN] {
+        match self {
+            Self::Sha1(key) => key.session_id(),
+            Self::Sha256(key) => key.session_id(),
+            Self::Sha512(key) => key.session_id(),
+            Self::Sha3_256(key) => key.session_id(),
+            Self::Sha3_512(key) => key.session_id(),
+            Self::Blake2b512(key) => key.session_id(),
+        }
+    }
+
+    pub fn confirmation(&self) -> KeyConfirmation {
+        match self {
+            Self::Sha1(key) => key.confirmation(),
+            Self::Sha256(key) => key.confirmation(),
+            Self::Sha512(key) => key.confirmation(),
+            Self::Sha3_256(key) => key.confirmation(),
+            Self::Sha3_512(key) => key.confirmation(),
+            Self::Blake2b512(key) => key.confirmation(),
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use alloc::{vec, vec::Vec};
+
+use num_bigint::BigUint;
+
+use crate::{SrpAuthError, SrpGroup};
+
+pub const EAP_TYPE_SRP_SHA1: u8 = 19;
+
+const EAP_REQUEST: u8 = 1;
+const EAP_RESPONSE: u8 = 2;
+
+const SUBTYPE_CHALLENGE: u8 = 1;
+const SUBTYPE_KEY: u8 = 2;
+const SUBTYPE_VALIDATOR: u8 = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EapSrpMessage {
+    ChallengeRequest {
+        name: Vec<u8>,
+        salt: Vec<u8>,
+        generator: Vec<u8>,
+        modulus: Vec<u8>,
+    },
+    ChallengeResponse {
+        a_pub: Vec<u8>,
+    },
+    KeyRequest {
+        b_pub: Vec<u8>,
+    },
+    KeyResponse {
+        m1: Vec<u8>,
+    },
+    ValidatorRequest {
+        flags: u32,
+        m2: Vec<u8>,
+    },
+    ValidatorResponse,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EapSrpPacket {
+    pub identifier: u8,
+    pub message: EapSrpMessage,
+}
+
+fn short_field(buf: &mut Vec<u8>, value: &[u8]) -> Result<(), SrpAuthError> {
+    let len = u8::try_from(value.len()).map_err(|_| SrpAuthError::IllegalParameter("eap_srp"))?;
+    buf.push(len);
+    buf.extend_from_slice(value);
+    Ok(())
+}
+
+fn take_short_field<'a>(buf: &mut &'a [u8]) -> Result<&'a [u8], SrpAuthError> {
+    let (&len, rest) = buf
+        .split_first()
+        .ok_or(SrpAuthError::DecodeError("eap_srp"))?;
+    if rest.len() < len as usize {
+        return Err(SrpAuthError::DecodeError("eap_srp"));
+    }
+    let (value, rest) = rest.split_at(len as usize);
+    *buf = rest;
+    Ok(value)
+}
+
+impl EapSrpMessage {
+    pub fn is_request(&self) -> bool {
+        matches!(
+            self,
+            Self::ChallengeRequest { .. } | Self::KeyRequest { .. } | Self::ValidatorRequest { .. }
+        )
+    }
+
+    // The draft lets the server omit the generator, meaning g = 2.
+    pub fn group(&self) -> Option<SrpGroup> {
+        match self {
+            Self::ChallengeRequest {
+                generator, modulus, ..
+            } if !modulus.is_empty() => Some(SrpGroup {
+                n: BigUint::from_bytes_be(modulus),
+                g: if generator.is_empty() {
+                    BigUint::from(2u8)
+                } else {
+                    BigUint::from_bytes_be(generator)
+                },
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl EapSrpPacket {
+    // Fails when name, salt or generator exceed 255 bytes or the packet 65535.
+    pub fn encode(&self) -> Result<Vec<u8>, SrpAuthError> {
+        let code = if self.message.is_request() {
+            EAP_REQUEST
+        } else {
+            EAP_RESPONSE
+        };
+        let mut buf = vec![code, self.identifier, 0, 0, EAP_TYPE_SRP_SHA1];
+
+        match &self.message {
+            EapSrpMessage::ChallengeRequest {
+                name,
+                salt,
+                generator,
+                modulus,
+            } => {
+                buf.push(SUBTYPE_CHALLENGE);
+                short_field(&mut buf, name)?;
+                short_field(&mut buf, salt)?;
+                short_field(&mut buf, generator)?;
+                buf.extend_from_slice(modulus);
+            }
+            EapSrpMessage::ChallengeResponse { a_pub } => {
+                buf.push(SUBTYPE_CHALLENGE);
+                buf.extend_from_slice(a_pub);
+            }
+            EapSrpMessage::KeyRequest { b_pub } => {
+                buf.push(SUBTYPE_KEY);
+                buf.extend_from_slice(b_pub);
+            }
+            EapSrpMessage::KeyResponse { m1 } => {
+                buf.push(SUBTYPE_KEY);
+                buf.extend_from_slice(m1);
+            }
+            EapSrpMessage::ValidatorRequest { flags, m2 } => {
+                buf.push(SUBTYPE_VALIDATOR);
+                buf.extend_from_slice(&flags.to_be_bytes());
+                buf.extend_from_slice(m2);
+            }
+            EapSrpMessage::ValidatorResponse => buf.push(SUBTYPE_VALIDATOR),
+        }
+
+        let len =
+            u16::try_from(buf.len()).map_err(|_| SrpAuthError::IllegalParameter("eap_srp"))?;
+        buf[2..4].copy_from_slice(&len.to_be_bytes());
+        Ok(buf)
+    }
+
+    pub fn decode(buf: &[u8]) -> Result<Self, SrpAuthError> {
+        let err = SrpAuthError::DecodeError("eap_srp");
+        let Some((&[code, identifier, l0, l1, ty, subtype], mut body)) = buf.split_first_chunk()
+        else {
+            return Err(err);
+        };
+        if ty != EAP_TYPE_SRP_SHA1 || u16::from_be_bytes([l0, l1]) as usize != buf.len() {
+            return Err(err);
+        }
+
+        let message = match (code, subtype) {
+            (EAP_REQUEST, SUBTYPE_CHALLENGE) => EapSrpMessage::ChallengeRequest {
+                name: take_short_field(&mut body)?.to_vec(),
+                salt: take_short_field(&mut body)?.to_vec(),
+                generator: take_short_field(&mut body)?.to_vec(),
+                modulus: body.to_vec(),
+            },
+            (EAP_RESPONSE, SUBTYPE_CHALLENGE) => EapSrpMessage::ChallengeResponse {
+                a_pub: body.to_vec(),
+            },
+            (EAP_REQUEST, SUBTYPE_KEY) => EapSrpMessage::KeyRequest {
+                b_pub: body.to_vec(),
+            },
+            (EAP_RESPONSE, SUBTYPE_KEY) => EapSrpMessage::KeyResponse { m1: body.to_vec() },
+            (EAP_REQUEST, SUBTYPE_VALIDATOR) => {
+                let (flags, m2) = body.split_first_chunk::<4>().ok_or(err)?;
+                EapSrpMessage::ValidatorRequest {
+                    flags: u32::from_be_bytes(*flags),
+                    m2: m2.to_vec(),
+                }
+            }
+            (EAP_RESPONSE, SUBTYPE_VALIDATOR) if body.is_empty() => {
+                EapSrpMessage::ValidatorResponse
+            }
+            _ => return Err(err),
+        };
+
+        Ok(Self {
+            identifier,
+            message,
+        })
+    }
+}
@@ -0,0 +1,49 @@
+use rand_core::{CryptoRng, CryptoRngCore, RngCore, impls};
+use zeroize::Zeroizing;
+
+pub const EPHEMERAL_LEN: usize = 32;
+
+// Adapts a host-provided entropy source (a WASM import, a plugin sandbox API,
+// a hardware RNG) into the `CryptoRngCore` that `register` and the setup-code
+// helpers take, so targets such as wasm32-unknown-unknown need no `getrandom`
+// backend. The callback must fill the whole buffer with secure random bytes.
+pub struct EntropyCallback<F> {
+    fill: F,
+}
+
+impl<F: FnMut(&mut [u8])> EntropyCallback<F> {
+    pub fn new(fill: F) -> Self {
+        Self { fill }
+    }
+}
+
+impl<F: FnMut(&mut [u8])> RngCore for EntropyCallback<F> {
+    fn next_u32(&mut self) -> u32 {
+        impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        (self.fill)(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        (self.fill)(dest);
+        Ok(())
+    }
+}
+
+impl<F: FnMut(&mut [u8])> CryptoRng for EntropyCallback<F> {}
+
+// A fresh private ephemeral `a` for `compute_public_ephemeral` and
+// `process_reply`.
+pub fn generate_ephemeral<R: CryptoRngCore + ?Sized>(
+    rng: &mut R,
+) -> Zeroizing<[u8; EPHEMERAL_LEN]> {
+    let mut a = Zeroizing::new([0; EPHEMERAL_LEN]);
+    rng.fill_bytes(&mut *a);
+    a
+}
@@ -0,0 +1,112 @@
+use alloc::{borrow::ToOwned, format, string::String, vec, vec::Vec};
+
+use crate::{
+    RegistrationData, SrpAuthError,
+    hex::{decode_hex, encode_hex_int},
+};
+
+const SRP_B64: &[u8; 64] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz./";
+
+pub fn encode_openssl_b64(bytes: &[u8]) -> String {
+    let pad = (3 - bytes.len() % 3) % 3;
+    let mut buf = vec![0; pad];
+    buf.extend_from_slice(bytes);
+
+    let mut out = String::with_capacity(buf.len() / 3 * 4);
+    for chunk in buf.chunks_exact(3) {
+        let n = u32::from_be_bytes([0, chunk[0], chunk[1], chunk[2]]);
+        for shift in [18, 12, 6, 0] {
+            out.push(SRP_B64[(n >> shift & 0x3f) as usize] as char);
+        }
+    }
+    out.split_off(pad)
+}
+
+pub fn decode_openssl_b64(encoded: &str) -> Result<Vec<u8>, SrpAuthError> {
+    const ERR: SrpAuthError = SrpAuthError::DecodeError("openssl_b64");
+    let pad = (4 - encoded.len() % 4) % 4;
+    if encoded.is_empty() || pad == 3 {
+        return Err(ERR);
+    }
+
+    let mut digits = vec![0u8; pad];
+    for c in encoded.bytes() {
+        let digit = SRP_B64.iter().position(|&d| d == c).ok_or(ERR)?;
+        digits.push(digit as u8);
+    }
+
+    let mut out = Vec::with_capacity(digits.len() / 4 * 3);
+    for chunk in digits.chunks_exact(4) {
+        let n = chunk.iter().fold(0u32, |n, &d| n << 6 | d as u32);
+        out.extend_from_slice(&n.to_be_bytes()[1..]);
+    }
+    if out[..pad].iter().any(|&b| b != 0) {
+        return Err(ERR);
+    }
+    Ok(out.split_off(pad))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenSslVerifierRecord {
+    pub username: String,
+    pub registration: RegistrationData,
+    pub group_id: String,
+    pub info: String,
+}
+
+impl OpenSslVerifierRecord {
+    pub fn parse_line(line: &str) -> Result<Self, SrpAuthError> {
+        let err = SrpAuthError::DecodeError("srpvfile");
+        let fields: Vec<&str> = line.trim_end_matches(['\r', '\n']).split('\t').collect();
+        let [kind, verifier, salt, username, group_id, info] = fields[..] else {
+            return Err(err);
+        };
+        if kind != "V" {
+            return Err(err);
+        }
+
+        Ok(Self {
+            username: username.to_owned(),
+            registration: RegistrationData {
+                salt: decode_openssl_b64(salt)?,
+                verifier: decode_openssl_b64(verifier)?,
+            },
+            group_id: group_id.to_owned(),
+            info: info.to_owned(),
+        })
+    }
+
+    pub fn to_line(&self) -> String {
+        format!(
+            "V\t{}\t{}\t{}\t{}\t{}\n",
+            encode_openssl_b64(&self.registration.verifier),
+            encode_openssl_b64(&self.registration.salt),
+            self.username,
+            self.group_id,
+            self.info,
+        )
+    }
+}
+
+pub fn parse_openssl_verifier_file(
+    contents: &str,
+) -> Result<Vec<OpenSslVerifierRecord>, SrpAuthError> {
+    contents
+        .lines()
+        .filter(|line| line.starts_with("V\t"))
+        .map(OpenSslVerifierRecord::parse_line)
+        .collect()
+}
+
+impl RegistrationData {
+    pub fn from_thinbus(salt: &str, verifier: &str) -> Result<Self, SrpAuthError> {
+        Ok(Self {
+            salt: decode_hex(salt)?,
+            verifier: decode_hex(verifier)?,
+        })
+    }
+
+    pub fn to_thinbus(&self) -> (String, String) {
+        (encode_hex_int(&self.salt), encode_hex_int(&self.verifier))
+    }
+}
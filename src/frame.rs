@@ -0,0 +1,100 @@
+use alloc::vec::Vec;
+
+use crate::SrpAuthError;
+
+pub const FRAME_HEADER_LEN: usize = 4;
+pub const MAX_FRAME_LEN: usize = 0x2000;
+
+// Refuses a payload the peer's `decode_frame` with the same `max_len` would
+// reject, or one too long for the header to describe.
+pub fn encode_frame(payload: &[u8], max_len: usize) -> Result<Vec<u8>, SrpAuthError> {
+    let len = u32::try_from(payload.len())
+        .ok()
+        .filter(|_| payload.len() <= max_len)
+        .ok_or(SrpAuthError::IllegalParameter("frame"))?;
+    let mut frame = Vec::with_capacity(FRAME_HEADER_LEN + payload.len());
+    frame.extend_from_slice(&len.to_be_bytes());
+    frame.extend_from_slice(payload);
+    Ok(frame)
+}
+
+fn frame_len(buf: &[u8], max_len: usize) -> Result<Option<usize>, SrpAuthError> {
+    let Some(header) = buf.first_chunk::<FRAME_HEADER_LEN>() else {
+        return Ok(None);
+    };
+    let len = u32::from_be_bytes(*header) as usize;
+    if len > max_len {
+        return Err(SrpAuthError::DecodeError("frame"));
+    }
+    Ok(Some(len))
+}
+
+pub fn decode_frame(buf: &[u8], max_len: usize) -> Result<Option<(&[u8], usize)>, SrpAuthError> {
+    match frame_len(buf, max_len)? {
+        Some(len) if buf.len() >= FRAME_HEADER_LEN + len => {
+            let end = FRAME_HEADER_LEN + len;
+            Ok(Some((&buf[FRAME_HEADER_LEN..end], end)))
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(feature = "tokio-util")]
+pub use codec::FrameCodec;
+
+#[cfg(feature = "tokio-util")]
+mod codec {
+    use std::io;
+
+    use bytes::{Buf, BytesMut};
+    use tokio_util::codec::{Decoder, Encoder};
+
+    use super::{FRAME_HEADER_LEN, MAX_FRAME_LEN, encode_frame, frame_len};
+
+    #[derive(Debug, Clone, Copy)]
+    pub struct FrameCodec {
+        max_len: usize,
+    }
+
+    impl FrameCodec {
+        pub fn new(max_len: usize) -> Self {
+            Self { max_len }
+        }
+    }
+
+    impl Default for FrameCodec {
+        fn default() -> Self {
+            Self::new(MAX_FRAME_LEN)
+        }
+    }
+
+    impl Decoder for FrameCodec {
+        type Item = BytesMut;
+        type Error = io::Error;
+
+        fn decode(&mut self, src: &mut BytesMut) -> Result<Option<BytesMut>, io::Error> {
+            let Some(len) = frame_len(src, self.max_len)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+            else {
+                return Ok(None);
+            };
+            if src.len() < FRAME_HEADER_LEN + len {
+                src.reserve(FRAME_HEADER_LEN + len - src.len());
+                return Ok(None);
+            }
+            src.advance(FRAME_HEADER_LEN);
+            Ok(Some(src.split_to(len)))
+        }
+    }
+
+    impl Encoder<&[u8]> for FrameCodec {
+        type Error = io::Error;
+
+        fn encode(&mut self, item: &[u8], dst: &mut BytesMut) -> Result<(), io::Error> {
+            let frame = encode_frame(item, self.max_len)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            dst.extend_from_slice(&frame);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use crate::{
+    EapSrpPacket, G_2048, GroupChecks, PairingsResponse, ServerChallenge, ServerProof, decode_tlv8,
+    validate,
+};
+
+// Entry points for cargo-fuzz and oss-fuzz targets, e.g.
+// `fuzz_target!(|data: &[u8]| fuzz_tlv8_decode(data));`. Each takes arbitrary
+// bytes, runs one parsing layer and the checks that sit right behind it, and
+// discards the result; any panic is a bug in the parser.
+
+pub fn fuzz_parse_server_reply(data: &[u8]) {
+    if let Ok(challenge) = ServerChallenge::decode(data) {
+        let _ = validate::salt(&challenge.salt);
+        let _ = validate::b_pub(&challenge.b_pub, &G_2048, false);
+        let _ = validate::b_pub(&challenge.b_pub, &G_2048, true);
+    }
+    let _ = ServerProof::decode(data);
+}
+
+pub fn fuzz_tlv8_decode(data: &[u8]) {
+    let _ = decode_tlv8(data);
+    let _ = PairingsResponse::decode(data);
+}
+
+// Groups arrive in EAP-SRP challenge requests. The checks reject anything
+// under 2048 bits before doing any arithmetic, so short inputs stay cheap.
+pub fn fuzz_group_parse(data: &[u8]) {
+    let Ok(packet) = EapSrpPacket::decode(data) else {
+        return;
+    };
+    if let Some(group) = packet.message.group() {
+        let _ = GroupChecks::default().check(&group);
+    }
+}
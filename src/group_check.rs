@@ -0,0 +1,179 @@
+use alloc::vec;
+#[cfg(feature = "std")]
+use std::sync::LazyLock;
+
+use num_bigint::BigUint;
+use rand_core::CryptoRngCore;
+
+use crate::{SrpAuthError, SrpGroup};
+
+const SMALL_PRIMES: [u8; 25] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+];
+
+#[cfg(feature = "std")]
+static WEAK_MODULI: LazyLock<[BigUint; 4]> = LazyLock::new(|| {
+    [
+        BigUint::from_bytes_be(include_bytes!("1024.bin")),
+        BigUint::from_bytes_be(include_bytes!("1536.bin")),
+        BigUint::from_bytes_be(include_bytes!("rfc2409_768.bin")),
+        BigUint::from_bytes_be(include_bytes!("rfc2409_1024.bin")),
+    ]
+});
+
+// Moduli that `GroupChecks` refuses by default whatever `min_bits` says: the
+// 1024- and 1536-bit RFC 5054 groups and the RFC 2409 Oakley groups 1 and 2,
+// whose discrete logs are within reach of a precomputation (Logjam). Extend
+// it by passing a longer slice as `rejected_moduli`. Without `std` there is
+// no lazy static to hold them, so `GroupChecks` has no default and callers
+// list what they reject themselves.
+#[cfg(feature = "std")]
+pub static KNOWN_WEAK_MODULI: LazyLock<[&'static BigUint; 4]> = LazyLock::new(|| {
+    let [a, b, c, d] = &*WEAK_MODULI;
+    [a, b, c, d]
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeneratorOrder {
+    // g is a quadratic residue and generates the prime-order subgroup of
+    // order (N-1)/2.
+    Subgroup,
+    // g is a non-residue and generates the whole group of order N-1, as
+    // g = 2 does for the RFC 5054 groups.
+    Full,
+}
+
+// Sanity checks for groups that did not ship with the crate. They assume N is
+// a safe prime and only catch what follows from that: a wrong-sized or listed
+// modulus, and a generator of order 1 or 2.
+#[derive(Debug, Clone, Copy)]
+pub struct GroupChecks<'a> {
+    pub min_bits: u64,
+    pub require_subgroup: bool,
+    pub rejected_moduli: &'a [&'a BigUint],
+}
+
+#[cfg(feature = "std")]
+impl Default for GroupChecks<'_> {
+    fn default() -> Self {
+        Self {
+            min_bits: 2048,
+            require_subgroup: false,
+            rejected_moduli: &*KNOWN_WEAK_MODULI,
+        }
+    }
+}
+
+impl GroupChecks<'_> {
+    pub fn check(&self, params: &SrpGroup) -> Result<GeneratorOrder, SrpAuthError> {
+        const GROUP: SrpAuthError = SrpAuthError::IllegalParameter("group");
+        const GENERATOR: SrpAuthError = SrpAuthError::IllegalParameter("generator");
+
+        let one = BigUint::from(1u8);
+        let n = &params.n;
+        if n.bits() < self.min_bits || !n.bit(0) || self.rejected_moduli.contains(&n) {
+            return Err(GROUP);
+        }
+        let n_minus_one = n - &one;
+        if params.g <= one || params.g >= n_minus_one {
+            return Err(GENERATOR);
+        }
+
+        // Euler's criterion: g^((N-1)/2) is 1 for residues and -1 otherwise.
+        // Anything else means N is not prime.
+        let legendre = params.g.modpow(&(&n_minus_one >> 1), n);
+        let order = if legendre == one {
+            GeneratorOrder::Subgroup
+        } else if legendre == n_minus_one {
+            GeneratorOrder::Full
+        } else {
+            return Err(GROUP);
+        };
+        if self.require_subgroup && order != GeneratorOrder::Subgroup {
+            return Err(GENERATOR);
+        }
+        Ok(order)
+    }
+}
+
+// Miller-Rabin with `rounds` random bases; a composite survives with at most
+// 4^-rounds probability, even when it was chosen by an attacker.
+pub fn is_probable_prime<R: CryptoRngCore + ?Sized>(n: &BigUint, rounds: u32, rng: &mut R) -> bool {
+    for p in SMALL_PRIMES {
+        if *n == BigUint::from(p) {
+            return true;
+        }
+        if (n % p) == BigUint::default() {
+            return false;
+        }
+    }
+    if n.bits() < 8 {
+        return false;
+    }
+
+    let one = BigUint::from(1u8);
+    let n_minus_one = n - &one;
+    let s = n_minus_one.trailing_zeros().unwrap_or(0);
+    let d = &n_minus_one >> s;
+    let mut bytes = vec![0; n.bits().div_ceil(8) as usize];
+
+    'rounds: for _ in 0..rounds {
+        rng.fill_bytes(&mut bytes);
+        let a = BigUint::from_bytes_be(&bytes) % (n - 3u8) + 2u8;
+        let mut x = a.modpow(&d, n);
+        if x == one || x == n_minus_one {
+            continue;
+        }
+        for _ in 1..s {
+            x = x.modpow(&BigUint::from(2u8), n);
+            if x == n_minus_one {
+                continue 'rounds;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+// Verifies that a group received over the wire has a safe-prime modulus:
+// both N and (N-1)/2 pass `rounds` of Miller-Rabin. Groups matching G_2048 or
+// one of `known_groups` skip the test, which costs two full-size modular
+// exponentiations per round.
+#[derive(Clone, Copy)]
+pub struct PrimalityCheck<'a> {
+    pub rounds: u32,
+    pub known_groups: &'a [&'a SrpGroup],
+}
+
+impl Default for PrimalityCheck<'_> {
+    fn default() -> Self {
+        Self {
+            rounds: 40,
+            known_groups: &[],
+        }
+    }
+}
+
+impl PrimalityCheck<'_> {
+    pub fn is_known(&self, params: &SrpGroup) -> bool {
+        let same = |known: &SrpGroup| known.n == params.n && known.g == params.g;
+        params.is_rfc5054_2048() || self.known_groups.iter().any(|known| same(known))
+    }
+
+    pub fn check<R: CryptoRngCore + ?Sized>(
+        &self,
+        params: &SrpGroup,
+        rng: &mut R,
+    ) -> Result<(), SrpAuthError> {
+        if self.is_known(params) {
+            return Ok(());
+        }
+        let n = &params.n;
+        if !is_probable_prime(n, self.rounds, rng)
+            || !is_probable_prime(&(n >> 1), self.rounds, rng)
+        {
+            return Err(SrpAuthError::IllegalParameter("group"));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,279 @@
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+
+use chacha20poly1305::{
+    ChaCha20Poly1305, KeyInit, Nonce,
+    aead::{AeadInPlace, Tag},
+};
+use sha2::Sha512;
+
+use crate::{HapKdf, SrpAuthError};
+
+pub const HAP_MAX_FRAME_LEN: usize = 1024;
+
+const LENGTH_LEN: usize = 2;
+const TAG_LEN: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HapMessageKind {
+    Response,
+    Event,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HapMessage {
+    pub kind: HapMessageKind,
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HapMessage {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+struct Direction {
+    cipher: ChaCha20Poly1305,
+    counter: u64,
+}
+
+impl Direction {
+    fn new(key: &[u8; 32]) -> Self {
+        Self {
+            cipher: ChaCha20Poly1305::new(key.into()),
+            counter: 0,
+        }
+    }
+
+    fn next_nonce(&mut self) -> Nonce {
+        let mut nonce = Nonce::default();
+        nonce[4..].copy_from_slice(&self.counter.to_le_bytes());
+        self.counter += 1;
+        nonce
+    }
+}
+
+// The controller's side of the encrypted stream that follows Pair-Verify. Each
+// frame is a little-endian plaintext length (also the AAD), up to 1024 bytes
+// of ciphertext and a Poly1305 tag; each direction counts its own nonces.
+pub struct HapSession {
+    read: Direction,
+    write: Direction,
+    pending: Vec<u8>,
+    failed: Option<SrpAuthError>,
+}
+
+impl HapSession {
+    pub fn new(read_key: &[u8; 32], write_key: &[u8; 32]) -> Self {
+        Self {
+            read: Direction::new(read_key),
+            write: Direction::new(write_key),
+            pending: Vec::new(),
+            failed: None,
+        }
+    }
+
+    pub fn from_shared_secret(shared_secret: &[u8]) -> Self {
+        let read_key = HapKdf::ControlRead.derive::<Sha512>(shared_secret);
+        let write_key = HapKdf::ControlWrite.derive::<Sha512>(shared_secret);
+        Self::new(&read_key, &write_key)
+    }
+
+    pub fn encrypt(&mut self, plaintext: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(plaintext.len() + LENGTH_LEN + TAG_LEN);
+        for chunk in plaintext.chunks(HAP_MAX_FRAME_LEN) {
+            let aad = (chunk.len() as u16).to_le_bytes();
+            let mut frame = chunk.to_vec();
+            let nonce = self.write.next_nonce();
+            let tag = self
+                .write
+                .cipher
+                .encrypt_in_place_detached(&nonce, &aad, &mut frame)
+                .expect("frame fits in a single AEAD message");
+            out.extend_from_slice(&aad);
+            out.extend_from_slice(&frame);
+            out.extend_from_slice(&tag);
+        }
+        out
+    }
+
+    // Buffers `data` and returns the plaintext of every frame it completed. A
+    // partial trailing frame is kept until more data arrives. A frame that is
+    // oversized or fails authentication leaves the read nonce out of step
+    // for good: the frames before it are still returned, but that call or
+    // the next and every one after fails, and the connection must be closed.
+    pub fn decrypt(&mut self, data: &[u8]) -> Result<Vec<u8>, SrpAuthError> {
+        if let Some(err) = self.failed {
+            return Err(err);
+        }
+        self.pending.extend_from_slice(data);
+
+        let mut plaintext = Vec::new();
+        let mut consumed = 0;
+        while let Some(&[lo, hi]) = self.pending[consumed..].first_chunk::<LENGTH_LEN>() {
+            let len = u16::from_le_bytes([lo, hi]) as usize;
+            if len > HAP_MAX_FRAME_LEN {
+                self.failed = Some(SrpAuthError::DecodeError("frame"));
+                break;
+            }
+            let end = consumed + LENGTH_LEN + len + TAG_LEN;
+            if self.pending.len() < end {
+                break;
+            }
+
+            let body = &self.pending[consumed + LENGTH_LEN..end - TAG_LEN];
+            let tag = Tag::<ChaCha20Poly1305>::clone_from_slice(&self.pending[end - TAG_LEN..end]);
+            let mut frame = body.to_vec();
+            let nonce = self.read.next_nonce();
+            if self
+                .read
+                .cipher
+                .decrypt_in_place_detached(&nonce, &[lo, hi], &mut frame, &tag)
+                .is_err()
+            {
+                self.failed = Some(SrpAuthError::BadRecordMac("frame"));
+                break;
+            }
+            plaintext.extend_from_slice(&frame);
+            consumed = end;
+        }
+
+        if self.failed.is_some() {
+            self.pending.clear();
+        } else {
+            self.pending.drain(..consumed);
+        }
+        match self.failed {
+            Some(err) if plaintext.is_empty() => Err(err),
+            _ => Ok(plaintext),
+        }
+    }
+
+    pub fn is_failed(&self) -> bool {
+        self.failed.is_some()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct HapMessageAssembler {
+    buf: Vec<u8>,
+}
+
+impl HapMessageAssembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, plaintext: &[u8]) {
+        self.buf.extend_from_slice(plaintext);
+    }
+
+    // Returns the next complete HTTP response or EVENT/1.0 notification, which
+    // may have spanned any number of frames. Bodies are delimited by
+    // Content-Length or chunked transfer encoding.
+    pub fn next_message(&mut self) -> Result<Option<HapMessage>, SrpAuthError> {
+        const ERR: SrpAuthError = SrpAuthError::DecodeError("http");
+
+        let Some(head_len) = find(&self.buf, b"\r\n\r\n") else {
+            return Ok(None);
+        };
+        let head = core::str::from_utf8(&self.buf[..head_len]).map_err(|_| ERR)?;
+        let mut lines = head.split("\r\n");
+
+        let mut start = lines.next().ok_or(ERR)?.splitn(3, ' ');
+        let kind = match start.next() {
+            Some("EVENT/1.0") => HapMessageKind::Event,
+            Some("HTTP/1.0" | "HTTP/1.1") => HapMessageKind::Response,
+            _ => return Err(ERR),
+        };
+        let status = start
+            .next()
+            .and_then(|status| status.parse().ok())
+            .ok_or(ERR)?;
+
+        let headers = lines
+            .map(|line| {
+                let (name, value) = line.split_once(':').ok_or(ERR)?;
+                Ok((name.trim().to_owned(), value.trim().to_owned()))
+            })
+            .collect::<Result<Vec<_>, SrpAuthError>>()?;
+        let mut message = HapMessage {
+            kind,
+            status,
+            headers,
+            body: Vec::new(),
+        };
+
+        let rest = &self.buf[head_len + 4..];
+        let consumed = if message
+            .header("Transfer-Encoding")
+            .is_some_and(|value| value.eq_ignore_ascii_case("chunked"))
+        {
+            match decode_chunked(rest)? {
+                Some((body, used)) => {
+                    message.body = body;
+                    used
+                }
+                None => return Ok(None),
+            }
+        } else {
+            let len = match message.header("Content-Length") {
+                Some(len) => len.parse::<usize>().map_err(|_| ERR)?,
+                None => 0,
+            };
+            if rest.len() < len {
+                return Ok(None);
+            }
+            message.body = rest[..len].to_vec();
+            len
+        };
+
+        self.buf.drain(..head_len + 4 + consumed);
+        Ok(Some(message))
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn decode_chunked(buf: &[u8]) -> Result<Option<(Vec<u8>, usize)>, SrpAuthError> {
+    const ERR: SrpAuthError = SrpAuthError::DecodeError("http");
+
+    let mut body = Vec::new();
+    let mut pos = 0;
+    loop {
+        let Some(line_len) = find(&buf[pos..], b"\r\n") else {
+            return Ok(None);
+        };
+        let line = core::str::from_utf8(&buf[pos..pos + line_len]).map_err(|_| ERR)?;
+        let size = line.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size, 16).map_err(|_| ERR)?;
+        pos += line_len + 2;
+
+        // The size is the accessory's word, so it must not overflow the
+        // arithmetic or reach past what has arrived.
+        let end = pos
+            .checked_add(size)
+            .and_then(|end| end.checked_add(2))
+            .ok_or(ERR)?;
+        if buf.len() < end {
+            return Ok(None);
+        }
+        let (chunk, crlf) = buf[pos..end].split_at(size);
+        if crlf != b"\r\n" {
+            return Err(ERR);
+        }
+        body.extend_from_slice(chunk);
+        pos = end;
+        if size == 0 {
+            return Ok(Some((body, pos)));
+        }
+    }
+}
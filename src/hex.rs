@@ -0,0 +1,53 @@
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+
+use digest::Digest;
+
+use crate::SrpAuthError;
+
+const HEX: &[u8; 16] = b"0123456789abcdef";
+
+pub fn encode_hex(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for &b in bytes {
+        out.push(HEX[(b >> 4) as usize] as char);
+        out.push(HEX[(b & 0xf) as usize] as char);
+    }
+    out
+}
+
+pub fn decode_hex(hex: &str) -> Result<Vec<u8>, SrpAuthError> {
+    fn nibble(c: u8) -> Result<u8, SrpAuthError> {
+        match c {
+            b'0'..=b'9' => Ok(c - b'0'),
+            b'a'..=b'f' => Ok(c - b'a' + 10),
+            b'A'..=b'F' => Ok(c - b'A' + 10),
+            _ => Err(SrpAuthError::DecodeError("hex")),
+        }
+    }
+
+    let hex = hex.as_bytes();
+    let (head, rest) = hex.split_at(hex.len() % 2);
+    let mut out = Vec::with_capacity(hex.len().div_ceil(2));
+    if let [c] = head {
+        out.push(nibble(*c)?);
+    }
+    for pair in rest.chunks_exact(2) {
+        out.push(nibble(pair[0])? << 4 | nibble(pair[1])?);
+    }
+    Ok(out)
+}
+
+pub(crate) fn encode_hex_int(bytes: &[u8]) -> String {
+    let hex = encode_hex(bytes);
+    match hex.trim_start_matches('0') {
+        "" => "0".to_owned(),
+        trimmed => trimmed.to_owned(),
+    }
+}
+
+pub(crate) fn hex_digest<D: Digest>(mut d: D, values: &[&[u8]]) -> D {
+    for value in values {
+        d.update(encode_hex_int(value));
+    }
+    d
+}
@@ -0,0 +1,203 @@
+use jni::{
+    JNIEnv,
+    objects::{JByteArray, JClass, JString},
+    sys::{jbyteArray, jlong},
+};
+
+use crate::{CompatProfile, DigestAlgorithm, DynSrpClient, DynSrpClientVerifier, SrpAuthError};
+
+// Native half of `java/io/github/kekeimiku/srp/SrpClient.java`. Clients and
+// verifiers cross the boundary as boxed handles in a `long`; Java owns them
+// and must release each one exactly once. SrpAuthError becomes
+// SrpAuthException and bad arguments become IllegalArgumentException.
+const AUTH_EXCEPTION: &str = "io/github/kekeimiku/srp/SrpAuthException";
+const ARGUMENT_EXCEPTION: &str = "java/lang/IllegalArgumentException";
+const RUNTIME_EXCEPTION: &str = "java/lang/RuntimeException";
+
+enum JavaError {
+    Auth(SrpAuthError),
+    Argument(String),
+    Jni(jni::errors::Error),
+}
+
+impl From<SrpAuthError> for JavaError {
+    fn from(err: SrpAuthError) -> Self {
+        JavaError::Auth(err)
+    }
+}
+
+impl From<jni::errors::Error> for JavaError {
+    fn from(err: jni::errors::Error) -> Self {
+        JavaError::Jni(err)
+    }
+}
+
+fn throw(env: &mut JNIEnv<'_>, err: JavaError) {
+    let _ = match err {
+        JavaError::Auth(err) => env.throw_new(AUTH_EXCEPTION, err.to_string()),
+        JavaError::Argument(message) => env.throw_new(ARGUMENT_EXCEPTION, message),
+        // The JVM already has this exception pending.
+        JavaError::Jni(jni::errors::Error::JavaException) => Ok(()),
+        JavaError::Jni(err) => env.throw_new(RUNTIME_EXCEPTION, err.to_string()),
+    };
+}
+
+fn bytes_result(env: &mut JNIEnv<'_>, result: Result<Vec<u8>, JavaError>) -> jbyteArray {
+    match result.and_then(|bytes| Ok(env.byte_array_from_slice(&bytes)?)) {
+        Ok(array) => array.into_raw(),
+        Err(err) => {
+            throw(env, err);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+fn handle_result(env: &mut JNIEnv<'_>, result: Result<jlong, JavaError>) -> jlong {
+    result.unwrap_or_else(|err| {
+        throw(env, err);
+        0
+    })
+}
+
+// SAFETY for every handle helper: Java only ever passes back handles produced
+// by `Box::into_raw` below and never after releasing them.
+unsafe fn client<'a>(handle: jlong) -> &'a DynSrpClient<'static> {
+    unsafe { &*(handle as *const DynSrpClient<'static>) }
+}
+
+fn optional_name(env: &mut JNIEnv<'_>, name: &JString<'_>) -> Result<Option<String>, JavaError> {
+    if name.is_null() {
+        return Ok(None);
+    }
+    Ok(Some(env.get_string(name)?.into()))
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_io_github_kekeimiku_srp_SrpClient_nativeNew<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    profile: JString<'local>,
+    digest: JString<'local>,
+) -> jlong {
+    let result = (|| {
+        let profile = match optional_name(&mut env, &profile)? {
+            Some(name) => CompatProfile::from_name(&name)
+                .ok_or_else(|| JavaError::Argument(format!("unknown profile '{name}'")))?,
+            None => CompatProfile::default(),
+        };
+        let algorithm = match optional_name(&mut env, &digest)? {
+            Some(name) => DigestAlgorithm::from_name(&name)
+                .ok_or_else(|| JavaError::Argument(format!("unknown digest '{name}'")))?,
+            None => profile.digest(),
+        };
+        let client = DynSrpClient::with_options(profile.group(), algorithm, profile.options());
+        Ok(Box::into_raw(Box::new(client)) as jlong)
+    })();
+    handle_result(&mut env, result)
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_io_github_kekeimiku_srp_SrpClient_nativeFree<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+) {
+    if handle != 0 {
+        drop(unsafe { Box::from_raw(handle as *mut DynSrpClient<'static>) });
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_io_github_kekeimiku_srp_SrpClient_nativeComputeVerifier<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    username: JByteArray<'local>,
+    password: JByteArray<'local>,
+    salt: JByteArray<'local>,
+) -> jbyteArray {
+    let result = (|| {
+        let username = env.convert_byte_array(&username)?;
+        let password = env.convert_byte_array(&password)?;
+        let salt = env.convert_byte_array(&salt)?;
+        Ok(unsafe { client(handle) }.compute_verifier(&username, &password, &salt))
+    })();
+    bytes_result(&mut env, result)
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_io_github_kekeimiku_srp_SrpClient_nativeComputePublicEphemeral<
+    'local,
+>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    a: JByteArray<'local>,
+) -> jbyteArray {
+    let result = (|| {
+        let a = env.convert_byte_array(&a)?;
+        Ok(unsafe { client(handle) }.compute_public_ephemeral(&a)?)
+    })();
+    bytes_result(&mut env, result)
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_io_github_kekeimiku_srp_SrpClient_nativeProcessReply<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    a: JByteArray<'local>,
+    username: JByteArray<'local>,
+    password: JByteArray<'local>,
+    salt: JByteArray<'local>,
+    b_pub: JByteArray<'local>,
+) -> jlong {
+    let result = (|| {
+        let a = env.convert_byte_array(&a)?;
+        let username = env.convert_byte_array(&username)?;
+        let password = env.convert_byte_array(&password)?;
+        let salt = env.convert_byte_array(&salt)?;
+        let b_pub = env.convert_byte_array(&b_pub)?;
+        let verifier =
+            unsafe { client(handle) }.process_reply(&a, &username, &password, &salt, &b_pub)?;
+        Ok(Box::into_raw(Box::new(verifier)) as jlong)
+    })();
+    handle_result(&mut env, result)
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_io_github_kekeimiku_srp_SrpClient_nativeVerifierProof<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    verifier: jlong,
+) -> jbyteArray {
+    let verifier = unsafe { &*(verifier as *const DynSrpClientVerifier) };
+    bytes_result(&mut env, Ok(verifier.proof().to_vec()))
+}
+
+// Consumes the verifier handle whether or not the server proof is accepted.
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_io_github_kekeimiku_srp_SrpClient_nativeVerifyServer<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    verifier: jlong,
+    m2: JByteArray<'local>,
+) -> jbyteArray {
+    let verifier = unsafe { Box::from_raw(verifier as *mut DynSrpClientVerifier) };
+    let result = (|| {
+        let m2 = env.convert_byte_array(&m2)?;
+        Ok(verifier.verify_server(&m2)?.as_bytes().to_vec())
+    })();
+    bytes_result(&mut env, result)
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_io_github_kekeimiku_srp_SrpClient_nativeVerifierFree<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    verifier: jlong,
+) {
+    if verifier != 0 {
+        drop(unsafe { Box::from_raw(verifier as *mut DynSrpClientVerifier) });
+    }
+}
@@ -0,0 +1,182 @@
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+
+use digest::{Digest, core_api::BlockSizeUser};
+use hkdf::SimpleHkdf;
+use zeroize::Zeroizing;
+
+use crate::{SessionKey, SrpAuthError};
+
+pub const SESSION_KEY_LEN: usize = 32;
+pub const SESSION_IV_LEN: usize = 12;
+
+pub struct SessionKeys<D: Digest + BlockSizeUser + Clone> {
+    secret: Zeroizing<Vec<u8>>,
+    generation: u64,
+    client_write_key: Zeroizing<[u8; SESSION_KEY_LEN]>,
+    server_write_key: Zeroizing<[u8; SESSION_KEY_LEN]>,
+    client_write_iv: Zeroizing<[u8; SESSION_IV_LEN]>,
+    server_write_iv: Zeroizing<[u8; SESSION_IV_LEN]>,
+    d: PhantomData<D>,
+}
+
+fn expand<D: Digest + BlockSizeUser + Clone>(prk: &[u8], info: &[&[u8]], okm: &mut [u8]) {
+    SimpleHkdf::<D>::from_prk(prk)
+        .expect("PRK is at least one hash long")
+        .expand_multi_info(info, okm)
+        .expect("output fits in 255 hash blocks");
+}
+
+pub const HAP_KEY_LEN: usize = 32;
+
+// The HKDF salt and info pairs HAP uses to derive its 32-byte keys, always
+// with SHA-512. The first three take the Pair-Setup SRP session key, the
+// Pair-Verify one the X25519 shared secret and the Control ones the shared
+// secret of a finished Pair-Verify.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HapKdf {
+    PairSetupEncrypt,
+    PairSetupControllerSign,
+    PairSetupAccessorySign,
+    PairVerifyEncrypt,
+    ControlRead,
+    ControlWrite,
+}
+
+impl HapKdf {
+    pub fn salt(self) -> &'static [u8] {
+        match self {
+            HapKdf::PairSetupEncrypt => b"Pair-Setup-Encrypt-Salt",
+            HapKdf::PairSetupControllerSign => b"Pair-Setup-Controller-Sign-Salt",
+            HapKdf::PairSetupAccessorySign => b"Pair-Setup-Accessory-Sign-Salt",
+            HapKdf::PairVerifyEncrypt => b"Pair-Verify-Encrypt-Salt",
+            HapKdf::ControlRead | HapKdf::ControlWrite => b"Control-Salt",
+        }
+    }
+
+    pub fn info(self) -> &'static [u8] {
+        match self {
+            HapKdf::PairSetupEncrypt => b"Pair-Setup-Encrypt-Info",
+            HapKdf::PairSetupControllerSign => b"Pair-Setup-Controller-Sign-Info",
+            HapKdf::PairSetupAccessorySign => b"Pair-Setup-Accessory-Sign-Info",
+            HapKdf::PairVerifyEncrypt => b"Pair-Verify-Encrypt-Info",
+            HapKdf::ControlRead => b"Control-Read-Encryption-Key",
+            HapKdf::ControlWrite => b"Control-Write-Encryption-Key",
+        }
+    }
+
+    pub fn derive<D: Digest + BlockSizeUser + Clone>(
+        self,
+        ikm: &[u8],
+    ) -> Zeroizing<[u8; HAP_KEY_LEN]> {
+        let mut okm = Zeroizing::new([0; HAP_KEY_LEN]);
+        SimpleHkdf::<D>::new(Some(self.salt()), ikm)
+            .expand(self.info(), &mut *okm)
+            .expect("32 bytes is a valid HKDF output length");
+        okm
+    }
+}
+
+impl<D: Digest + BlockSizeUser + Clone> SessionKey<D> {
+    pub fn hap_key(&self, label: HapKdf) -> Zeroizing<[u8; HAP_KEY_LEN]> {
+        label.derive::<D>(self.as_bytes())
+    }
+
+    pub fn export_keying_material(
+        &self,
+        label: &[u8],
+        context: &[u8],
+        len: usize,
+    ) -> Result<Zeroizing<Vec<u8>>, SrpAuthError> {
+        let label_len =
+            u16::try_from(label.len()).map_err(|_| SrpAuthError::IllegalParameter("label"))?;
+        let context_len =
+            u16::try_from(context.len()).map_err(|_| SrpAuthError::IllegalParameter("context"))?;
+        let out_len = u16::try_from(len).map_err(|_| SrpAuthError::IllegalParameter("len"))?;
+
+        let mut okm = Zeroizing::new(vec![0; len]);
+        SimpleHkdf::<D>::new(None, self.as_bytes())
+            .expand_multi_info(
+                &[
+                    b"srp exporter",
+                    &label_len.to_be_bytes(),
+                    label,
+                    &context_len.to_be_bytes(),
+                    context,
+                    &out_len.to_be_bytes(),
+                ],
+                &mut okm,
+            )
+            .map_err(|_| SrpAuthError::IllegalParameter("len"))?;
+        Ok(okm)
+    }
+}
+
+impl<D: Digest + BlockSizeUser + Clone> SessionKeys<D> {
+    pub fn derive(key: &SessionKey<D>, context: &[u8]) -> Self {
+        let (prk, _) = SimpleHkdf::<D>::extract(None, key.as_bytes());
+        let mut secret = Zeroizing::new(vec![0; <D as Digest>::output_size()]);
+        expand::<D>(&prk, &[b"srp traffic secret", context], &mut secret);
+        Self::from_secret(secret, 0)
+    }
+
+    pub fn next_keys(&self) -> Self {
+        let mut secret = Zeroizing::new(vec![0; <D as Digest>::output_size()]);
+        expand::<D>(&self.secret, &[b"srp traffic update"], &mut secret);
+        Self::from_secret(secret, self.generation + 1)
+    }
+
+    fn from_secret(secret: Zeroizing<Vec<u8>>, generation: u64) -> Self {
+        let mut keys = Self {
+            secret,
+            generation,
+            client_write_key: Zeroizing::new([0; SESSION_KEY_LEN]),
+            server_write_key: Zeroizing::new([0; SESSION_KEY_LEN]),
+            client_write_iv: Zeroizing::new([0; SESSION_IV_LEN]),
+            server_write_iv: Zeroizing::new([0; SESSION_IV_LEN]),
+            d: PhantomData,
+        };
+        let secret = &keys.secret;
+        expand::<D>(
+            secret,
+            &[b"srp client write key"],
+            &mut *keys.client_write_key,
+        );
+        expand::<D>(
+            secret,
+            &[b"srp server write key"],
+            &mut *keys.server_write_key,
+        );
+        expand::<D>(
+            secret,
+            &[b"srp client write iv"],
+            &mut *keys.client_write_iv,
+        );
+        expand::<D>(
+            secret,
+            &[b"srp server write iv"],
+            &mut *keys.server_write_iv,
+        );
+        keys
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn client_write_key(&self) -> &[u8] {
+        &*self.client_write_key
+    }
+
+    pub fn server_write_key(&self) -> &[u8] {
+        &*self.server_write_key
+    }
+
+    pub fn client_write_iv(&self) -> &[u8] {
+        &*self.client_write_iv
+    }
+
+    pub fn server_write_iv(&self) -> &[u8] {
+        &*self.server_write_iv
+    }
+}
@@ -0,0 +1,276 @@
+use std::{
+    fmt, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use chacha20poly1305::{
+    ChaCha20Poly1305, KeyInit, Nonce,
+    aead::{Aead, Payload},
+};
+use rand_core::{OsRng, RngCore};
+use serde_json::{Value, json};
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+use crate::{
+    LongTermKeys, MemoryPairingStore, Pairing, PairingStore, decode_hex, encode_hex,
+    pairing_json::{decode_store, encode_store},
+};
+
+const KEYRING_VERSION: u64 = 1;
+const KEYRING_AAD: &[u8] = b"srp pairing keyring v1";
+const PBKDF2_ITERATIONS: u32 = 600_000;
+// The KDF parameters sit in the unauthenticated envelope, so a tampered file
+// could otherwise downgrade the cost or make `open` spin for hours.
+const PBKDF2_MAX_ITERATIONS: u32 = 10_000_000;
+const PBKDF2_SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+pub enum KeyringKey {
+    Passphrase(Zeroizing<Vec<u8>>),
+    Wrapped(Zeroizing<[u8; 32]>),
+}
+
+#[derive(Debug)]
+pub enum KeyringError {
+    Io(io::Error),
+    Malformed,
+    Decrypt,
+}
+
+impl fmt::Display for KeyringError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyringError::Io(err) => write!(f, "keyring i/o error: {err}"),
+            KeyringError::Malformed => write!(f, "keyring file is malformed"),
+            KeyringError::Decrypt => write!(f, "keyring could not be decrypted"),
+        }
+    }
+}
+
+impl std::error::Error for KeyringError {}
+
+impl From<io::Error> for KeyringError {
+    fn from(err: io::Error) -> Self {
+        KeyringError::Io(err)
+    }
+}
+
+// The file is a JSON envelope naming the KDF and holding a ChaCha20-Poly1305
+// ciphertext of the JSON keyring itself. Every change rewrites the whole file
+// through a temporary sibling and a rename, with a fresh nonce.
+pub struct FileKeyring {
+    path: PathBuf,
+    kdf: Value,
+    key: Zeroizing<[u8; 32]>,
+    store: MemoryPairingStore,
+}
+
+impl FileKeyring {
+    pub fn open(path: impl AsRef<Path>, key: KeyringKey) -> Result<Self, KeyringError> {
+        let path = path.as_ref().to_path_buf();
+        let envelope = match fs::read(&path) {
+            Ok(bytes) => {
+                Some(serde_json::from_slice::<Value>(&bytes).map_err(|_| KeyringError::Malformed)?)
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err.into()),
+        };
+
+        let kdf = match &envelope {
+            Some(envelope) => envelope["kdf"].clone(),
+            None => new_kdf(&key),
+        };
+        let key = derive_key(&kdf, key)?;
+
+        let mut keyring = Self {
+            path,
+            kdf,
+            key,
+            store: MemoryPairingStore::new(),
+        };
+        if let Some(envelope) = envelope {
+            keyring.load(&envelope)?;
+        }
+        Ok(keyring)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn load(&mut self, envelope: &Value) -> Result<(), KeyringError> {
+        if envelope["version"].as_u64() != Some(KEYRING_VERSION) {
+            return Err(KeyringError::Malformed);
+        }
+        let nonce = hex_field(&envelope["nonce"])?;
+        let ciphertext = hex_field(&envelope["ciphertext"])?;
+        let plaintext = open(&self.key, KEYRING_AAD, &nonce, &ciphertext)?;
+        self.store = decode_store(&plaintext).ok_or(KeyringError::Malformed)?;
+        Ok(())
+    }
+
+    // Applies `change` to a copy and only adopts it once the file has been
+    // replaced, so a failed write leaves memory matching what is on disk.
+    fn update(&mut self, change: impl FnOnce(&mut MemoryPairingStore)) -> Result<(), KeyringError> {
+        let mut store = self.store.clone();
+        change(&mut store);
+        self.flush(&store)?;
+        self.store = store;
+        Ok(())
+    }
+
+    fn flush(&self, store: &MemoryPairingStore) -> Result<(), KeyringError> {
+        let plaintext = encode_store(store);
+
+        let (nonce, ciphertext) = seal(&self.key, KEYRING_AAD, &plaintext);
+
+        let envelope = json!({
+            "version": KEYRING_VERSION,
+            "kdf": self.kdf,
+            "nonce": encode_hex(&nonce),
+            "ciphertext": encode_hex(&ciphertext),
+        });
+        let tmp = self.path.with_extension("tmp");
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options.open(&tmp)?;
+        file.write_all(&serde_json::to_vec_pretty(&envelope).expect("envelope is valid JSON"))?;
+        // The data must be on disk before the rename can expose it, and the
+        // rename itself is only durable once the directory is synced.
+        file.sync_all()?;
+        drop(file);
+        fs::rename(&tmp, &self.path)?;
+        #[cfg(unix)]
+        {
+            let dir = match self.path.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir,
+                _ => Path::new("."),
+            };
+            fs::File::open(dir)?.sync_all()?;
+        }
+        Ok(())
+    }
+}
+
+// A fresh KDF description for a new store: PBKDF2 with a random salt for a
+// passphrase, nothing for a key that is already random.
+pub(crate) fn new_kdf(key: &KeyringKey) -> Value {
+    match key {
+        KeyringKey::Passphrase(_) => {
+            let mut salt = [0; PBKDF2_SALT_LEN];
+            OsRng.fill_bytes(&mut salt);
+            json!({
+                "name": "pbkdf2-sha256",
+                "iterations": PBKDF2_ITERATIONS,
+                "salt": encode_hex(&salt),
+            })
+        }
+        KeyringKey::Wrapped(_) => json!({ "name": "none" }),
+    }
+}
+
+// ChaCha20-Poly1305 under a fresh random nonce, which is returned alongside
+// the ciphertext.
+pub(crate) fn seal(key: &[u8; 32], aad: &[u8], plaintext: &[u8]) -> ([u8; NONCE_LEN], Vec<u8>) {
+    let mut nonce = [0; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+    let cipher = ChaCha20Poly1305::new(key.into());
+    let payload = Payload {
+        msg: plaintext,
+        aad,
+    };
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), payload)
+        .expect("plaintext fits in a single AEAD message");
+    (nonce, ciphertext)
+}
+
+pub(crate) fn open(
+    key: &[u8; 32],
+    aad: &[u8],
+    nonce: &[u8],
+    ciphertext: &[u8],
+) -> Result<Zeroizing<Vec<u8>>, KeyringError> {
+    if nonce.len() != NONCE_LEN {
+        return Err(KeyringError::Malformed);
+    }
+    let cipher = ChaCha20Poly1305::new(key.into());
+    let payload = Payload {
+        msg: ciphertext,
+        aad,
+    };
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce), payload)
+        .map_err(|_| KeyringError::Decrypt)?;
+    Ok(Zeroizing::new(plaintext))
+}
+
+fn hex_field(value: &Value) -> Result<Vec<u8>, KeyringError> {
+    let hex = value.as_str().ok_or(KeyringError::Malformed)?;
+    decode_hex(hex).map_err(|_| KeyringError::Malformed)
+}
+
+pub(crate) fn derive_key(
+    kdf: &Value,
+    key: KeyringKey,
+) -> Result<Zeroizing<[u8; 32]>, KeyringError> {
+    match (kdf["name"].as_str(), key) {
+        (Some("pbkdf2-sha256"), KeyringKey::Passphrase(passphrase)) => {
+            let iterations = kdf["iterations"]
+                .as_u64()
+                .and_then(|iterations| u32::try_from(iterations).ok())
+                .filter(|iterations| {
+                    (PBKDF2_ITERATIONS..=PBKDF2_MAX_ITERATIONS).contains(iterations)
+                })
+                .ok_or(KeyringError::Malformed)?;
+            let salt = hex_field(&kdf["salt"])?;
+            let mut key = Zeroizing::new([0; 32]);
+            pbkdf2::pbkdf2_hmac::<Sha256>(&passphrase, &salt, iterations, key.as_mut());
+            Ok(key)
+        }
+        (Some("none"), KeyringKey::Wrapped(key)) => Ok(key),
+        (Some("pbkdf2-sha256" | "none"), _) => Err(KeyringError::Decrypt),
+        _ => Err(KeyringError::Malformed),
+    }
+}
+
+impl PairingStore for FileKeyring {
+    type Error = KeyringError;
+
+    fn load_keys(&self) -> Result<Option<LongTermKeys>, KeyringError> {
+        let Ok(keys) = self.store.load_keys();
+        Ok(keys)
+    }
+
+    fn save_keys(&mut self, keys: &LongTermKeys) -> Result<(), KeyringError> {
+        self.update(|store| {
+            let Ok(()) = store.save_keys(keys);
+        })
+    }
+
+    fn load_pairing(&self, identifier: &[u8]) -> Result<Option<Pairing>, KeyringError> {
+        let Ok(pairing) = self.store.load_pairing(identifier);
+        Ok(pairing)
+    }
+
+    fn save_pairing(&mut self, pairing: &Pairing) -> Result<(), KeyringError> {
+        self.update(|store| {
+            let Ok(()) = store.save_pairing(pairing);
+        })
+    }
+
+    fn remove_pairing(&mut self, identifier: &[u8]) -> Result<(), KeyringError> {
+        self.update(|store| {
+            let Ok(()) = store.remove_pairing(identifier);
+        })
+    }
+
+    fn list_pairings(&self) -> Result<Vec<Pairing>, KeyringError> {
+        let Ok(pairings) = self.store.list_pairings();
+        Ok(pairings)
+    }
+}
@@ -1,14 +1,173 @@
+// The protocol core (math, proofs, message codecs, `drive_pake`) needs only
+// `alloc`; the `std` feature adds the built-in groups, clocks, std::io
+// codecs and stores.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+// Only an rlib is declared, so dependents never build a shared library. The
+// capi, napi and jni bindings are linked with
+// `cargo rustc --lib --release --features <binding> --crate-type cdylib`;
+// Swift links the same way with `--crate-type staticlib`.
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+mod attempts;
+mod audit;
+mod ble;
+#[cfg(feature = "capi")]
+#[allow(clippy::missing_safety_doc)]
+mod capi;
+#[cfg(feature = "std")]
+mod credential_cache;
+mod ct;
+// defmt's interned-string symbols cannot go into a hosted cdylib export
+// list, so the impls only exist on bare-metal targets, where they are used.
+#[cfg(all(feature = "defmt", target_os = "none"))]
+mod defmt_format;
+mod discovery;
+mod driver;
+#[cfg(feature = "dyn-digest")]
+mod dyn_client;
+mod eap;
+mod entropy;
+mod formats;
+mod frame;
+#[cfg(feature = "fuzzing")]
+mod fuzz;
+mod group_check;
+#[cfg(feature = "hap-ip")]
+mod hap_ip;
+mod hex;
+#[cfg(feature = "jni")]
+mod java;
+mod kdf;
+#[cfg(feature = "keyring")]
+mod keyring;
+#[cfg(all(feature = "mlock", unix))]
+mod locked;
+mod message;
+#[cfg(feature = "std")]
+mod metrics;
+#[cfg(feature = "testing")]
+mod mock;
+mod negotiate;
+#[cfg(feature = "napi")]
+mod node;
+#[cfg(feature = "os-keychain")]
+mod os_keychain;
+mod pair_setup;
+mod pairing;
+#[cfg(any(feature = "keyring", feature = "os-keychain"))]
+mod pairing_json;
+mod pake;
+pub mod prelude;
+mod profile;
+mod secret;
+mod server;
+#[cfg(feature = "session-cipher")]
+mod session_cipher;
+mod setup_code;
+mod signing;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+#[cfg(feature = "std")]
+mod static_group;
+#[cfg(feature = "swift")]
+#[allow(clippy::unnecessary_cast)]
+mod swift;
+mod tlv8;
+mod transcript;
+pub mod validate;
+#[cfg(feature = "negative-vectors")]
+mod vectors;
+mod xof;
+
+#[cfg(feature = "std")]
+pub use attempts::*;
+pub use audit::*;
+pub use ble::*;
+#[cfg(feature = "capi")]
+pub use capi::*;
+#[cfg(feature = "std")]
+pub use credential_cache::*;
+pub use ct::*;
+pub use discovery::*;
+pub use driver::*;
+#[cfg(feature = "dyn-digest")]
+pub use dyn_client::*;
+pub use eap::*;
+pub use entropy::*;
+pub use formats::*;
+pub use frame::*;
+#[cfg(feature = "fuzzing")]
+pub use fuzz::*;
+pub use group_check::*;
+#[cfg(feature = "hap-ip")]
+pub use hap_ip::*;
+pub use hex::{decode_hex, encode_hex};
+pub use kdf::*;
+#[cfg(feature = "keyring")]
+pub use keyring::*;
+#[cfg(all(feature = "mlock", unix))]
+pub use locked::*;
+pub use message::*;
+#[cfg(feature = "std")]
+pub use metrics::*;
+#[cfg(feature = "testing")]
+pub use mock::*;
+pub use negotiate::*;
+#[cfg(feature = "napi")]
+pub use node::*;
+#[cfg(feature = "os-keychain")]
+pub use os_keychain::*;
+pub use pair_setup::*;
+pub use pairing::*;
+pub use pake::*;
+pub use profile::*;
+pub use server::*;
+#[cfg(feature = "session-cipher")]
+pub use session_cipher::*;
+pub use setup_code::*;
+pub use signing::*;
+#[cfg(feature = "sqlite")]
+pub use sqlite::*;
+#[cfg(feature = "std")]
+pub use static_group::*;
+#[cfg(feature = "swift")]
+pub use swift::*;
+pub use tlv8::*;
+pub use transcript::*;
+#[cfg(feature = "negative-vectors")]
+pub use vectors::*;
+pub use xof::*;
+// The dependency types that appear in this crate's signatures, so callers can
+// name them without pinning digest or num-bigint themselves.
+pub use digest::{self, Digest, Output};
+pub use num_bigint::{self, BigUint};
+
+use alloc::{borrow::Cow, vec, vec::Vec};
 use core::{fmt, marker::PhantomData};
-use std::sync::LazyLock;
+#[cfg(feature = "std")]
+use std::{
+    sync::LazyLock,
+    time::{Duration, SystemTime},
+};
+
+use digest::core_api::BlockSizeUser;
+use hmac::{Mac, SimpleHmac};
+use rand_core::CryptoRngCore;
+use zeroize::{Zeroize, Zeroizing};
 
-use digest::{Digest, Output};
-use num_bigint::BigUint;
-use subtle::ConstantTimeEq;
+use crate::{
+    hex::hex_digest,
+    secret::{SecretBytes, SecretUint, to_fixed_bytes, trim_leading_zeros},
+};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SrpAuthError {
     IllegalParameter(&'static str),
     BadRecordMac(&'static str),
+    DecodeError(&'static str),
 }
 
 impl fmt::Display for SrpAuthError {
@@ -20,38 +179,157 @@ impl fmt::Display for SrpAuthError {
             SrpAuthError::BadRecordMac(param) => {
                 write!(f, "bad_record_mac: incorrect '{param}'  proof")
             }
+            SrpAuthError::DecodeError(param) => {
+                write!(f, "decode_error: malformed '{param}' message")
+            }
         }
     }
 }
 
+impl core::error::Error for SrpAuthError {}
+
+pub const SALT_LEN: usize = 16;
+pub const SESSION_ID_LEN: usize = 16;
+pub const RESUMPTION_NONCE_LEN: usize = 32;
+
+#[derive(Clone)]
 pub struct SrpGroup {
     pub n: BigUint,
     pub g: BigUint,
 }
 
-pub static G_2048: LazyLock<SrpGroup> = LazyLock::new(|| SrpGroup {
-    n: BigUint::from_bytes_be(include_bytes!("2048.bin")),
-    g: BigUint::from_bytes_be(&[2]),
-});
+impl SrpGroup {
+    pub fn byte_len(&self) -> usize {
+        self.n.bits().div_ceil(8) as usize
+    }
+
+    // Left-pads `bytes` with zeros to |N|, as A, B, g and the verifier are
+    // for hashing. Longer input is returned unchanged.
+    pub fn pad_to_group(&self, bytes: &[u8]) -> Vec<u8> {
+        pad_to(bytes, self.byte_len())
+    }
+
+    // The RFC 5054 2048-bit group that `G_2048` holds, for builds without
+    // `std` that cannot have it as a lazy static.
+    pub fn rfc5054_2048() -> Self {
+        Self {
+            n: BigUint::from_bytes_be(N_2048),
+            g: BigUint::from(2u8),
+        }
+    }
+
+    // The RFC 5054 3072-bit group with g = 5, which HomeKit Pair-Setup uses.
+    pub fn rfc5054_3072() -> Self {
+        Self {
+            n: BigUint::from_bytes_be(N_3072),
+            g: BigUint::from(5u8),
+        }
+    }
+
+    pub(crate) fn is_rfc5054_2048(&self) -> bool {
+        self.g == BigUint::from(2u8) && self.n.to_bytes_be() == N_2048
+    }
+}
+
+const N_2048: &[u8] = include_bytes!("2048.bin");
+const N_3072: &[u8] = include_bytes!("3072.bin");
+
+#[cfg(feature = "std")]
+pub static G_2048: LazyLock<SrpGroup> = LazyLock::new(SrpGroup::rfc5054_2048);
+#[cfg(feature = "std")]
+pub static G_3072: LazyLock<SrpGroup> = LazyLock::new(SrpGroup::rfc5054_3072);
+
+// Always a wiped copy, so no normalized password outlives the hash it feeds.
+// The NFKD form is measured before it is written, so the buffer never grows
+// and leaves a stale copy behind.
+pub fn normalize_password(password: &[u8]) -> Zeroizing<Vec<u8>> {
+    #[cfg(feature = "nfkd")]
+    if let Ok(password) = core::str::from_utf8(password) {
+        use unicode_normalization::{IsNormalized, UnicodeNormalization, is_nfkd_quick};
+
+        if is_nfkd_quick(password.chars()) != IsNormalized::Yes {
+            let len = password.nfkd().map(char::len_utf8).sum();
+            let mut normalized = Zeroizing::new(Vec::with_capacity(len));
+            for c in password.nfkd() {
+                let mut buf = [0; 4];
+                normalized.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                buf.zeroize();
+            }
+            return normalized;
+        }
+    }
+    Zeroizing::new(password.to_vec())
+}
+
+fn pad_to(bytes: &[u8], len: usize) -> Vec<u8> {
+    let mut buf = vec![0u8; len.saturating_sub(bytes.len())];
+    buf.extend_from_slice(bytes);
+    buf
+}
 
 pub fn compute_u<D: Digest>(a_pub: &[u8], b_pub: &[u8]) -> BigUint {
-    let mut u = D::new();
-    u.update(a_pub);
-    u.update(b_pub);
-    BigUint::from_bytes_be(&u.finalize())
+    BigUint::from_bytes_be(&u_digest(D::new(), a_pub, b_pub).finalize())
+}
+
+fn u_digest<D: Digest>(mut d: D, a_pub: &[u8], b_pub: &[u8]) -> D {
+    d.update(a_pub);
+    d.update(b_pub);
+    d
+}
+
+fn x_digest<D: Digest>(mut d: D, identity_hash: &[u8], salt: &[u8], order: SaltOrder) -> BigUint {
+    for part in order.arrange(salt, identity_hash) {
+        d.update(part);
+    }
+    BigUint::from_bytes_be(&d.finalize())
+}
+
+fn k_digest<D: Digest>(mut d: D, n: &[u8], g: &[u8]) -> BigUint {
+    d.update(n);
+    d.update(g);
+    BigUint::from_bytes_be(d.finalize().as_slice())
 }
 
 pub fn compute_k<D: Digest>(params: &SrpGroup) -> BigUint {
     let n = params.n.to_bytes_be();
-    let g_bytes = params.g.to_bytes_be();
-    let mut buf = vec![0u8; n.len()];
-    let l = n.len() - g_bytes.len();
-    buf[l..].copy_from_slice(&g_bytes);
+    let g = params.pad_to_group(&params.g.to_bytes_be());
+    k_digest(D::new(), &n, &g)
+}
 
-    let mut d = D::new();
-    d.update(&n);
-    d.update(&buf);
-    BigUint::from_bytes_be(d.finalize().as_slice())
+// H(N) xor H(g), the group term that opens M1.
+fn group_hash<D: Digest>(n: &[u8], g: &[u8]) -> Output<D> {
+    let mut g_hash = D::digest(g);
+    let n_hash = D::digest(n);
+
+    for i in 0..g_hash.len() {
+        g_hash[i] ^= n_hash[i];
+    }
+    g_hash
+}
+
+fn m1_digest<D: Digest>(
+    mut d: D,
+    group_hash: &Output<D>,
+    a_pub: &[u8],
+    b_pub: &[u8],
+    key: &[u8],
+    username: &[u8],
+    salt: &[u8],
+) -> D {
+    d.update(group_hash);
+    d.update(D::digest(username));
+    d.update(salt);
+    d.update(a_pub);
+    d.update(b_pub);
+    d.update(key);
+    d
+}
+
+fn m2_digest<D: Digest>(mut d: D, a_pub: &[u8], m1: &Output<D>, key: &[u8]) -> D {
+    d.update(a_pub);
+    d.update(m1);
+    d.update(key);
+    d
 }
 
 pub fn compute_m1<D: Digest>(
@@ -63,55 +341,440 @@ pub fn compute_m1<D: Digest>(
     params: &SrpGroup,
 ) -> Output<D> {
     let n = params.n.to_bytes_be();
-    let g_bytes = params.g.to_bytes_be();
-
-    let mut g = vec![0; n.len() - g_bytes.len()];
-    g.extend_from_slice(&g_bytes);
+    let g = pad_to(&params.g.to_bytes_be(), n.len());
+    m1_digest(
+        D::new(),
+        &group_hash::<D>(&n, &g),
+        a_pub,
+        b_pub,
+        key,
+        username,
+        salt,
+    )
+    .finalize()
+}
 
-    let mut g_hash = D::digest(&g);
-    let n_hash = D::digest(&n);
+pub fn compute_m2<D: Digest>(a_pub: &[u8], m1: &Output<D>, key: &[u8]) -> Output<D> {
+    m2_digest(D::new(), a_pub, m1, key).finalize()
+}
 
-    for i in 0..g_hash.len() {
-        g_hash[i] ^= n_hash[i];
+// The M1 a server holding `verifier` and its private ephemeral `b` expects
+// for the captured A and B, under the default options: unpadded u and key,
+// g padded in M1 and no binding. Lets tooling check a recorded handshake
+// without standing up a server.
+pub fn recompute_m1_from_verifier<D: Digest>(
+    params: &SrpGroup,
+    username: &[u8],
+    salt: &[u8],
+    verifier: &[u8],
+    a_pub: &[u8],
+    b_pub: &[u8],
+    b: &[u8],
+) -> Result<Output<D>, SrpAuthError> {
+    validate::salt(salt)?;
+    let a = validate::a_pub(a_pub, params, false)?;
+    validate::b_pub(b_pub, params, false)?;
+    let v = BigUint::from_bytes_be(verifier);
+    if !validate::group_member(&v, params) {
+        return Err(SrpAuthError::IllegalParameter("verifier"));
     }
+    let u = compute_u::<D>(a_pub, b_pub);
+    validate::u(&u)?;
+
+    let n = &params.n;
+    let premaster = SecretUint(((a % n) * v.modpow(&u, n)).modpow(&BigUint::from_bytes_be(b), n));
+    let premaster = to_fixed_bytes(&premaster, params.byte_len());
+    let key = D::digest(trim_leading_zeros(&premaster));
+    Ok(compute_m1::<D>(a_pub, b_pub, &key, username, salt, params))
+}
+
+pub fn compute_group_binding<D: Digest>(params: &SrpGroup, digest_id: &[u8]) -> Output<D> {
+    let n = params.n.to_bytes_be();
+    let g = params.g.to_bytes_be();
 
     let mut d = D::new();
-    d.update(&g_hash);
-    d.update(D::digest(username));
-    d.update(salt);
-    d.update(a_pub);
-    d.update(b_pub);
-    d.update(key);
+    d.update((n.len() as u32).to_be_bytes());
+    d.update(&n);
+    d.update((g.len() as u32).to_be_bytes());
+    d.update(&g);
+    d.update((digest_id.len() as u32).to_be_bytes());
+    d.update(digest_id);
     d.finalize()
 }
 
-pub fn compute_m2<D: Digest>(a_pub: &[u8], m1: &Output<D>, key: &[u8]) -> Output<D> {
+pub fn compute_channel_binding<D: Digest>(binding: &[u8]) -> Output<D> {
     let mut d = D::new();
-    d.update(a_pub);
-    d.update(m1);
-    d.update(key);
+    d.update(b"apple_srp_client channel binding");
+    d.update((binding.len() as u32).to_be_bytes());
+    d.update(binding);
+    d.finalize()
+}
+
+pub fn compute_bound_m1<D: Digest>(
+    a_pub: &[u8],
+    b_pub: &[u8],
+    key: &[u8],
+    username: &[u8],
+    salt: &[u8],
+    params: &SrpGroup,
+    binding: &[u8],
+) -> Output<D> {
+    let n = params.n.to_bytes_be();
+    let g = pad_to(&params.g.to_bytes_be(), n.len());
+    let mut d = m1_digest(
+        D::new(),
+        &group_hash::<D>(&n, &g),
+        a_pub,
+        b_pub,
+        key,
+        username,
+        salt,
+    );
+    d.update(binding);
     d.finalize()
 }
 
+pub fn compute_bound_m2<D: Digest>(
+    a_pub: &[u8],
+    m1: &Output<D>,
+    key: &[u8],
+    binding: &[u8],
+) -> Output<D> {
+    let mut d = m2_digest(D::new(), a_pub, m1, key);
+    d.update(binding);
+    d.finalize()
+}
+
+// Named inputs for M1 and M2, so a transposed salt and username is caught at
+// the call site instead of surfacing as a proof mismatch. `binding` selects
+// the group-bound variants.
+#[derive(Clone, Copy)]
+pub struct ProofInputs<'a> {
+    pub username: &'a [u8],
+    pub salt: &'a [u8],
+    pub a_pub: &'a [u8],
+    pub b_pub: &'a [u8],
+    pub key: &'a [u8],
+    pub binding: Option<&'a [u8]>,
+}
+
+impl ProofInputs<'_> {
+    pub fn compute_m1<D: Digest>(&self, params: &SrpGroup) -> Output<D> {
+        let Self {
+            username,
+            salt,
+            a_pub,
+            b_pub,
+            key,
+            binding,
+        } = *self;
+        match binding {
+            Some(binding) => {
+                compute_bound_m1::<D>(a_pub, b_pub, key, username, salt, params, binding)
+            }
+            None => compute_m1::<D>(a_pub, b_pub, key, username, salt, params),
+        }
+    }
+
+    pub fn compute_m2<D: Digest>(&self, m1: &Output<D>) -> Output<D> {
+        match self.binding {
+            Some(binding) => compute_bound_m2::<D>(self.a_pub, m1, self.key, binding),
+            None => compute_m2::<D>(self.a_pub, m1, self.key),
+        }
+    }
+}
+
+fn compute_hmac<D: Digest + BlockSizeUser>(key: &[u8], label: &[u8], data: &[&[u8]]) -> Output<D> {
+    let mut mac = <SimpleHmac<D> as Mac>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(label);
+    for part in data {
+        mac.update(part);
+    }
+    mac.finalize().into_bytes()
+}
+
+pub fn compute_resumption_secret<D: Digest + BlockSizeUser>(key: &[u8]) -> Output<D> {
+    compute_hmac::<D>(key, b"srp resumption secret", &[])
+}
+
+// Both sides contribute freshness: the server's challenge and the client's
+// nonce, which goes back to the server alongside M1. Without the nonce a
+// server replaying a recorded (challenge, M2) pair would pass
+// `verify_server` and hand the client the old session key. The nonce has a
+// fixed length and comes first, so the concatenation is unambiguous.
+pub fn compute_resumption_proofs<D: Digest + BlockSizeUser>(
+    secret: &[u8],
+    client_nonce: &[u8; RESUMPTION_NONCE_LEN],
+    challenge: &[u8],
+) -> (Output<D>, Output<D>, Output<D>) {
+    let data = [&client_nonce[..], challenge];
+    let m1 = compute_hmac::<D>(secret, b"srp resumption client", &data);
+    let m2 = compute_hmac::<D>(secret, b"srp resumption server", &data);
+    let key = compute_hmac::<D>(secret, b"srp resumption key", &data);
+    (m1, m2, key)
+}
+
+// The hashes that `SrpOptions::domain_separation` prefixes, each with its own
+// tag. A server built on this crate must feed the same tags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashContext {
+    U,
+    K,
+    X,
+    M1,
+    M2,
+}
+
+impl HashContext {
+    pub fn tag(self) -> &'static [u8] {
+        match self {
+            HashContext::U => b"apple_srp_client u",
+            HashContext::K => b"apple_srp_client k",
+            HashContext::X => b"apple_srp_client x",
+            HashContext::M1 => b"apple_srp_client M1",
+            HashContext::M2 => b"apple_srp_client M2",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SaltOrder {
+    #[default]
+    SaltFirst,
+    SaltLast,
+}
+
+impl SaltOrder {
+    fn arrange<'b>(self, salt: &'b [u8], identity_hash: &'b [u8]) -> [&'b [u8]; 2] {
+        match self {
+            SaltOrder::SaltFirst => [salt, identity_hash],
+            SaltOrder::SaltLast => [identity_hash, salt],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Endianness {
+    #[default]
+    Big,
+    Little,
+}
+
+impl Endianness {
+    fn decode(self, bytes: &[u8]) -> Cow<'_, [u8]> {
+        match self {
+            Endianness::Big => Cow::Borrowed(bytes),
+            Endianness::Little => Cow::Owned(bytes.iter().rev().copied().collect()),
+        }
+    }
+
+    fn encode(self, mut bytes: Vec<u8>) -> Vec<u8> {
+        if self == Endianness::Little {
+            bytes.reverse();
+        }
+        bytes
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SrpOptions {
+    pub group_binding: Option<&'static [u8]>,
+    pub pad_g: bool,
+    pub pad_u: bool,
+    pub pad_key: bool,
+    pub preserve_b_encoding: bool,
+    pub hex_hashing: bool,
+    pub username_in_x: bool,
+    pub salt_order: SaltOrder,
+    pub wire_endianness: Endianness,
+    pub canonical_encoding: bool,
+    // Emit A and the verifier left-padded to |N| instead of minimally encoded;
+    // some servers reject a value whose leading byte happened to be zero.
+    pub pad_output: bool,
+    // Start u, k, x, M1 and M2 with their `HashContext` tag, so a digest
+    // from this handshake cannot be passed off as one from another protocol
+    // that reuses the password and hash. Both ends must agree; off for
+    // interop with plain SRP-6a.
+    pub domain_separation: bool,
+}
+
+impl Default for SrpOptions {
+    fn default() -> Self {
+        Self {
+            group_binding: None,
+            pad_g: true,
+            pad_u: false,
+            pad_key: false,
+            preserve_b_encoding: false,
+            hex_hashing: false,
+            username_in_x: false,
+            salt_order: SaltOrder::SaltFirst,
+            wire_endianness: Endianness::Big,
+            canonical_encoding: cfg!(feature = "harden"),
+            pad_output: cfg!(feature = "harden"),
+            domain_separation: false,
+        }
+    }
+}
+
+impl SrpOptions {
+    // Every strictness knob on, leaving the hashing choices that decide
+    // interop untouched. The `harden` feature makes this the default.
+    pub fn hardened(self) -> Self {
+        Self {
+            canonical_encoding: true,
+            pad_output: true,
+            ..self
+        }
+    }
+}
+
 pub struct SrpClient<'a, D: Digest> {
-    params: &'a SrpGroup,
+    params: Cow<'a, SrpGroup>,
+    options: SrpOptions,
+    channel_binding: Option<&'a [u8]>,
+    d: PhantomData<D>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistrationData {
+    pub salt: Vec<u8>,
+    pub verifier: Vec<u8>,
+}
+
+// x depends on the client's options, so the only way to build one is
+// `SrpClient::credentials`; pass it back to a client with the same options.
+pub struct Credentials<D: Digest> {
+    salt: Vec<u8>,
+    x: SecretBytes,
+    d: PhantomData<D>,
+}
+
+#[cfg(feature = "std")]
+pub struct ResumptionSecret<D: Digest> {
+    secret: Zeroizing<Vec<u8>>,
+    expires_at: SystemTime,
     d: PhantomData<D>,
 }
 
 pub struct SrpClientVerifier<D: Digest> {
     m1: Output<D>,
     m2: Output<D>,
-    key: Vec<u8>,
+    key: Zeroizing<Vec<u8>>,
+    #[cfg(feature = "unsafe-debug")]
+    intermediates: Option<HandshakeIntermediates>,
+}
+
+// Every secret of the exchange except `a` itself, for comparing against a
+// misbehaving server's logs. Anyone holding these can recover the password
+// verifier, so the feature refuses to build with optimizations on.
+#[cfg(feature = "unsafe-debug")]
+#[derive(Debug, Clone)]
+pub struct HandshakeIntermediates {
+    pub u: BigUint,
+    pub k: BigUint,
+    pub x: BigUint,
+    pub premaster_secret: BigUint,
+}
+
+#[cfg(all(feature = "unsafe-debug", not(debug_assertions)))]
+compile_error!("the `unsafe-debug` feature exposes handshake secrets and is for debug builds only");
+
+pub struct SessionKey<D: Digest> {
+    key: SecretBytes,
+    m1: Output<D>,
+    m2: Output<D>,
+    transcript: Output<D>,
+}
+
+// Everything public that confirmed a session key, for protocols that log or
+// co-sign the handshake. `transcript_hash` is H(M1 || M2), the value
+// `SessionKey::session_id` is derived from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyConfirmation {
+    pub m1: Vec<u8>,
+    pub m2: Vec<u8>,
+    pub transcript_hash: Vec<u8>,
+}
+
+// Written out rather than derived so neither needs `D: Clone`.
+impl<D: Digest> Clone for SrpClient<'_, D> {
+    fn clone(&self) -> Self {
+        Self {
+            params: self.params.clone(),
+            options: self.options,
+            channel_binding: self.channel_binding,
+            d: PhantomData,
+        }
+    }
+}
+
+impl<D: Digest> Clone for SrpClientVerifier<D> {
+    fn clone(&self) -> Self {
+        Self {
+            m1: self.m1.clone(),
+            m2: self.m2.clone(),
+            key: self.key.clone(),
+            #[cfg(feature = "unsafe-debug")]
+            intermediates: self.intermediates.clone(),
+        }
+    }
+}
+
+// Connection pools share clients and verifiers across threads; this fails to
+// compile if a field ever stops being Send or Sync.
+const _: () = {
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[allow(dead_code)]
+    fn assert_thread_safe<D: Digest + Send + Sync>() {
+        assert_send_sync::<SrpClient<'static, D>>();
+        assert_send_sync::<SrpClientVerifier<D>>();
+        assert_send_sync::<SessionKey<D>>();
+    }
+};
+
+impl<D: Digest> SrpClient<'static, D> {
+    // Owns the group, for clients built from runtime parameters that still
+    // need to be 'static to move into spawned tasks or long-lived structs.
+    pub fn with_owned_group(params: SrpGroup, options: SrpOptions) -> Self {
+        Self {
+            params: Cow::Owned(params),
+            options,
+            channel_binding: None,
+            d: PhantomData,
+        }
+    }
 }
 
 impl<'a, D: Digest> SrpClient<'a, D> {
     pub fn new(params: &'a SrpGroup) -> Self {
+        Self::with_options(params, SrpOptions::default())
+    }
+
+    pub fn with_options(params: &'a SrpGroup, options: SrpOptions) -> Self {
         Self {
-            params,
+            params: Cow::Borrowed(params),
+            options,
+            channel_binding: None,
             d: PhantomData,
         }
     }
 
+    // Mixes `binding`, e.g. a TLS exporter value for the connection SRP runs
+    // over, into M1 and M2 after any group binding. A MITM terminating the
+    // outer session sees a different value on each side, so the proofs stop
+    // matching. The server must mix in `compute_channel_binding` the same way.
+    pub fn with_channel_binding<'b>(self, binding: &'b [u8]) -> SrpClient<'b, D>
+    where
+        'a: 'b,
+    {
+        SrpClient {
+            channel_binding: Some(binding),
+            ..self
+        }
+    }
+
     pub fn compute_a_pub(&self, a: &BigUint) -> BigUint {
         self.params.g.modpow(a, &self.params.n)
     }
@@ -120,15 +783,24 @@ impl<'a, D: Digest> SrpClient<'a, D> {
         let mut d = D::new();
         d.update(username);
         d.update(b":");
-        d.update(password);
+        d.update(&*normalize_password(password));
         d.finalize()
     }
 
     pub fn compute_x(identity_hash: &[u8], salt: &[u8]) -> BigUint {
-        let mut x = D::new();
-        x.update(salt);
-        x.update(identity_hash);
-        BigUint::from_bytes_be(&x.finalize())
+        Self::compute_x_with_order(identity_hash, salt, SaltOrder::SaltFirst)
+    }
+
+    pub fn compute_x_with_order(identity_hash: &[u8], salt: &[u8], order: SaltOrder) -> BigUint {
+        x_digest(D::new(), identity_hash, salt, order)
+    }
+
+    fn hasher(&self, context: HashContext) -> D {
+        let mut d = D::new();
+        if self.options.domain_separation {
+            d.update(context.tag());
+        }
+        d
     }
 
     pub fn compute_premaster_secret(
@@ -145,18 +817,102 @@ impl<'a, D: Digest> SrpClient<'a, D> {
         base.modpow(&exp, &self.params.n)
     }
 
+    // As `compute_premaster_secret`, but refuses the inputs that would make S
+    // predictable instead of deriving a key from it: a modulus too small to
+    // reduce by, B congruent to k*g^x (so the base is zero), and S of 0 or 1.
+    pub fn try_compute_premaster_secret(
+        &self,
+        b_pub: &BigUint,
+        k: &BigUint,
+        x: &BigUint,
+        a: &BigUint,
+        u: &BigUint,
+    ) -> Result<BigUint, SrpAuthError> {
+        let n = &self.params.n;
+        let one = BigUint::from(1u8);
+        if *n <= one {
+            return Err(SrpAuthError::IllegalParameter("group"));
+        }
+        let v = SecretUint(self.params.g.modpow(x, n));
+        let kv = SecretUint((k * &*v) % n);
+        let b_pub = b_pub % n;
+        if b_pub == *kv {
+            return Err(SrpAuthError::IllegalParameter("b_pub"));
+        }
+        let base = SecretUint((n + b_pub - &*kv) % n);
+        let exponent = SecretUint((u * x) + a);
+        let premaster = base.modpow(&exponent, n);
+        if premaster <= one {
+            return Err(SrpAuthError::IllegalParameter("premaster_secret"));
+        }
+        Ok(premaster)
+    }
+
     pub fn compute_v(&self, x: &BigUint) -> BigUint {
         self.params.g.modpow(x, &self.params.n)
     }
 
+    // Always hashes the username into x, as this function always has, but
+    // otherwise follows `hex_hashing` and the other x options. Apple's
+    // handshake leaves the username out, so under the default options use
+    // `register` for a verifier that `process_reply` will match.
     pub fn compute_verifier(&self, username: &[u8], password: &[u8], salt: &[u8]) -> Vec<u8> {
-        let identity_hash = Self::compute_identity_hash(username, password);
-        let x = Self::compute_x(identity_hash.as_slice(), salt);
-        self.compute_v(&x).to_bytes_be()
+        self.verifier_for(&self.derive_credentials(username, password, salt, true))
+    }
+
+    fn verifier_for(&self, credentials: &Credentials<D>) -> Vec<u8> {
+        let x = SecretUint(BigUint::from_bytes_be(&credentials.x));
+        let v = self.output_bytes(&self.compute_v(&x));
+        self.options.wire_endianness.encode(v)
+    }
+
+    // Recomputes v with the same x derivation as `process_reply`, so `true`
+    // means a handshake with these credentials will succeed. `username` only
+    // matters under `username_in_x`, which every profile but Apple sets;
+    // otherwise x ignores it and any value gives the same answer.
+    pub fn verify_password_against_verifier(
+        &self,
+        username: &[u8],
+        password: &[u8],
+        salt: &[u8],
+        verifier: &[u8],
+    ) -> bool {
+        // Both sides are left-padded to |N| so a stored verifier that kept
+        // or dropped leading zero bytes still compares equal.
+        let endianness = self.options.wire_endianness;
+        let computed = self.verifier_for(&self.credentials(username, password, salt));
+        let computed = Zeroizing::new(self.params.pad_to_group(&endianness.decode(&computed)));
+        let expected = self.params.pad_to_group(&endianness.decode(verifier));
+        constant_time_eq(&expected, &computed)
+    }
+
+    pub fn register<R: CryptoRngCore + ?Sized>(
+        &self,
+        username: &[u8],
+        password: &[u8],
+        rng: &mut R,
+    ) -> RegistrationData {
+        let mut salt = vec![0; SALT_LEN];
+        rng.fill_bytes(&mut salt);
+        let verifier = self.verifier_for(&self.credentials(username, password, &salt));
+        RegistrationData { salt, verifier }
     }
 
-    pub fn compute_public_ephemeral(&self, a: &[u8]) -> Vec<u8> {
-        self.compute_a_pub(&BigUint::from_bytes_be(a)).to_bytes_be()
+    // Checks `a` the way `process_reply` will, so an A that the same `a`
+    // would later be refused for is never sent.
+    pub fn compute_public_ephemeral(&self, a: &[u8]) -> Result<Vec<u8>, SrpAuthError> {
+        let a = SecretUint(validate::ephemeral(a, &self.params)?);
+        let a_pub = self.output_bytes(&self.compute_a_pub(&a));
+        Ok(self.options.wire_endianness.encode(a_pub))
+    }
+
+    fn output_bytes(&self, value: &BigUint) -> Vec<u8> {
+        let bytes = value.to_bytes_be();
+        if self.options.pad_output {
+            self.params.pad_to_group(&bytes)
+        } else {
+            bytes
+        }
     }
 
     pub fn process_reply(
@@ -167,55 +923,341 @@ impl<'a, D: Digest> SrpClient<'a, D> {
         salt: &[u8],
         b_pub: &[u8],
     ) -> Result<SrpClientVerifier<D>, SrpAuthError> {
-        let a = BigUint::from_bytes_be(a);
-        let a_pub = self.compute_a_pub(&a);
-        let b_pub = BigUint::from_bytes_be(b_pub);
+        let credentials = self.credentials(username, password, salt);
+        self.process_reply_with_credentials(a, username, &credentials, b_pub)
+    }
 
-        if &b_pub % &self.params.n == BigUint::default() {
-            return Err(SrpAuthError::IllegalParameter("b_pub"));
-        }
+    pub fn credentials(&self, username: &[u8], password: &[u8], salt: &[u8]) -> Credentials<D> {
+        self.derive_credentials(username, password, salt, self.options.username_in_x)
+    }
 
-        let u = compute_u::<D>(&a_pub.to_bytes_be(), &b_pub.to_bytes_be());
-        let k = compute_k::<D>(self.params);
-        let identity_hash = Self::compute_identity_hash(&[], password);
-        let x = Self::compute_x(identity_hash.as_slice(), salt);
+    fn derive_credentials(
+        &self,
+        username: &[u8],
+        password: &[u8],
+        salt: &[u8],
+        username_in_x: bool,
+    ) -> Credentials<D> {
+        let salt = &*self.options.wire_endianness.decode(salt);
+        let username = if username_in_x { username } else { &[] };
 
-        let key = self.compute_premaster_secret(&b_pub, &k, &x, &a, &u);
-        let key = D::digest(key.to_bytes_be());
+        let mut identity_hash = Self::compute_identity_hash(username, password);
+        let d = self.hasher(HashContext::X);
+        let x = SecretUint(if self.options.hex_hashing {
+            let parts = self.options.salt_order.arrange(salt, &identity_hash);
+            BigUint::from_bytes_be(&hex_digest(d, &parts).finalize())
+        } else {
+            x_digest(d, &identity_hash, salt, self.options.salt_order)
+        });
+        identity_hash.as_mut_slice().zeroize();
+        Credentials::from_x(salt, &x)
+    }
 
-        let m1 = compute_m1::<D>(
-            &a_pub.to_bytes_be(),
-            &b_pub.to_bytes_be(),
-            &key,
-            username,
-            salt,
-            self.params,
-        );
+    pub fn process_reply_with_credentials(
+        &self,
+        a: &[u8],
+        username: &[u8],
+        credentials: &Credentials<D>,
+        b_pub: &[u8],
+    ) -> Result<SrpClientVerifier<D>, SrpAuthError> {
+        let salt = credentials.salt();
+        // A short, zero or out-of-range `a` makes A guessable (A = 1 for
+        // a = 0).
+        let a = SecretUint(validate::ephemeral(a, &self.params)?);
+        let a_pub = self.compute_a_pub(&a);
+        let b_pub = &*self.options.wire_endianness.decode(b_pub);
 
-        let m2 = compute_m2::<D>(&a_pub.to_bytes_be(), &m1, &key);
+        validate::salt(salt)?;
+        let b_received = b_pub;
+        let b_pub = validate::b_pub(b_pub, &self.params, self.options.canonical_encoding)?;
+        validate::reflection(&a_pub, &b_pub, &self.params)?;
 
-        Ok(SrpClientVerifier {
+        let a_bytes = a_pub.to_bytes_be();
+        let b_bytes = if self.options.preserve_b_encoding {
+            b_received.to_vec()
+        } else {
+            b_pub.to_bytes_be()
+        };
+
+        let u = self.compute_scrambler(&a_bytes, &b_bytes)?;
+        let k = self.compute_multiplier();
+        let x = SecretUint(BigUint::from_bytes_be(&credentials.x));
+
+        let premaster = SecretUint(self.try_compute_premaster_secret(&b_pub, &k, &x, &a, &u)?);
+        #[cfg(feature = "unsafe-debug")]
+        let intermediates = HandshakeIntermediates {
+            u: u.clone(),
+            k: k.clone(),
+            x: x.0.clone(),
+            premaster_secret: premaster.0.clone(),
+        };
+        let premaster = to_fixed_bytes(&premaster, self.params.byte_len());
+        let (m1, m2, key) = self.confirm(username, salt, &a_bytes, &b_bytes, &premaster);
+
+        let verifier = SrpClientVerifier {
             m1,
             m2,
-            key: key.to_vec(),
-        })
+            key,
+            #[cfg(feature = "unsafe-debug")]
+            intermediates: Some(intermediates),
+        };
+        Ok(verifier)
+    }
+
+    // g as k and M1 hash it.
+    fn hashed_g(&self) -> Vec<u8> {
+        let g = self.params.g.to_bytes_be();
+        if self.options.pad_g {
+            self.params.pad_to_group(&g)
+        } else {
+            g
+        }
+    }
+
+    // u from A and B as hashed, which `SrpServer` shares with the client.
+    pub(crate) fn compute_scrambler(
+        &self,
+        a_bytes: &[u8],
+        b_bytes: &[u8],
+    ) -> Result<BigUint, SrpAuthError> {
+        let len = self.params.byte_len();
+        let d = self.hasher(HashContext::U);
+        let u = if self.options.hex_hashing {
+            hex_digest(d, &[a_bytes, b_bytes])
+        } else if self.options.pad_u {
+            u_digest(d, &pad_to(a_bytes, len), &pad_to(b_bytes, len))
+        } else {
+            u_digest(d, a_bytes, b_bytes)
+        };
+        let u = BigUint::from_bytes_be(&u.finalize());
+        validate::u(&u)?;
+        Ok(u)
+    }
+
+    pub(crate) fn compute_multiplier(&self) -> BigUint {
+        k_digest(
+            self.hasher(HashContext::K),
+            &self.params.n.to_bytes_be(),
+            &self.hashed_g(),
+        )
+    }
+
+    // K, M1 and M2 from the premaster secret in |N| bytes. Both roles end
+    // here, so they agree on every option once they agree on S.
+    pub(crate) fn confirm(
+        &self,
+        username: &[u8],
+        salt: &[u8],
+        a_bytes: &[u8],
+        b_bytes: &[u8],
+        premaster: &[u8],
+    ) -> (Output<D>, Output<D>, Zeroizing<Vec<u8>>) {
+        let mut key = if self.options.hex_hashing {
+            hex_digest(D::new(), &[trim_leading_zeros(premaster)]).finalize()
+        } else if self.options.pad_key {
+            D::digest(premaster)
+        } else {
+            D::digest(trim_leading_zeros(premaster))
+        };
+
+        let binding = self
+            .options
+            .group_binding
+            .map(|digest_id| compute_group_binding::<D>(&self.params, digest_id));
+        let channel_binding = self.channel_binding.map(compute_channel_binding::<D>);
+
+        let d = self.hasher(HashContext::M1);
+        let mut d = if self.options.hex_hashing {
+            hex_digest(d, &[a_bytes, b_bytes, premaster])
+        } else {
+            let group_hash = group_hash::<D>(&self.params.n.to_bytes_be(), &self.hashed_g());
+            m1_digest(d, &group_hash, a_bytes, b_bytes, &key, username, salt)
+        };
+        if let Some(binding) = &binding {
+            d.update(binding);
+        }
+        if let Some(channel_binding) = &channel_binding {
+            d.update(channel_binding);
+        }
+        let m1 = d.finalize();
+
+        let d = self.hasher(HashContext::M2);
+        let mut d = if self.options.hex_hashing {
+            hex_digest(d, &[a_bytes, &m1, premaster])
+        } else {
+            m2_digest(d, a_bytes, &m1, &key)
+        };
+        if let Some(binding) = &binding {
+            d.update(binding);
+        }
+        if let Some(channel_binding) = &channel_binding {
+            d.update(channel_binding);
+        }
+        let m2 = d.finalize();
+
+        let confirmed = Zeroizing::new(key.to_vec());
+        key.as_mut_slice().zeroize();
+        (m1, m2, confirmed)
     }
 }
 
-impl<D: Digest> SrpClientVerifier<D> {
-    pub fn key(&self) -> &[u8] {
-        &self.key
+impl<D: Digest> Credentials<D> {
+    fn from_x(salt: &[u8], x: &BigUint) -> Self {
+        Self {
+            salt: salt.to_vec(),
+            x: to_fixed_bytes(x, <D as Digest>::output_size()).into(),
+            d: PhantomData,
+        }
+    }
+
+    pub fn salt(&self) -> &[u8] {
+        &self.salt
     }
 
+    // The salt's length as a big-endian u32, the salt, then x, for the
+    // `CredentialStore` backends that persist credentials as one secret.
+    #[cfg(feature = "os-keychain")]
+    pub(crate) fn to_bytes(&self) -> Zeroizing<Vec<u8>> {
+        let mut out = Zeroizing::new(Vec::with_capacity(4 + self.salt.len() + self.x.len()));
+        out.extend_from_slice(&(self.salt.len() as u32).to_be_bytes());
+        out.extend_from_slice(&self.salt);
+        out.extend_from_slice(&self.x);
+        out
+    }
+
+    #[cfg(feature = "os-keychain")]
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let (len, rest) = bytes.split_first_chunk::<4>()?;
+        let salt = rest.get(..u32::from_be_bytes(*len) as usize)?;
+        let x = &rest[salt.len()..];
+        if x.len() != <D as Digest>::output_size() {
+            return None;
+        }
+        Some(Self {
+            salt: salt.to_vec(),
+            x: Zeroizing::new(x.to_vec()).into(),
+            d: PhantomData,
+        })
+    }
+}
+
+impl<D: Digest> SrpClientVerifier<D> {
     pub fn proof(&self) -> &[u8] {
         self.m1.as_slice()
     }
 
-    pub fn verify_server(&self, reply: &[u8]) -> Result<(), SrpAuthError> {
-        if self.m2.ct_eq(reply).unwrap_u8() != 1 {
+    pub fn verify_server(self, reply: &[u8]) -> Result<SessionKey<D>, SrpAuthError> {
+        if !constant_time_eq(&self.m2, reply) {
             Err(SrpAuthError::BadRecordMac("server"))
         } else {
-            Ok(())
+            Ok(self.into_session_key())
+        }
+    }
+
+    #[cfg(feature = "unverified-key")]
+    pub fn into_key_unverified(self) -> SessionKey<D> {
+        self.into_session_key()
+    }
+
+    // None for verifiers produced by session resumption, which runs no SRP
+    // arithmetic.
+    #[cfg(feature = "unsafe-debug")]
+    pub fn intermediates(&self) -> Option<&HandshakeIntermediates> {
+        self.intermediates.as_ref()
+    }
+
+    fn into_session_key(self) -> SessionKey<D> {
+        let transcript = D::new()
+            .chain_update(&self.m1)
+            .chain_update(&self.m2)
+            .finalize();
+        SessionKey {
+            key: self.key.into(),
+            m1: self.m1,
+            m2: self.m2,
+            transcript,
+        }
+    }
+}
+
+impl<D: Digest> SessionKey<D> {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.key
+    }
+
+    pub fn confirmation(&self) -> KeyConfirmation {
+        KeyConfirmation {
+            m1: self.m1.to_vec(),
+            m2: self.m2.to_vec(),
+            transcript_hash: self.transcript.to_vec(),
         }
     }
+
+    // A short, log-safe correlation ID bound to both the key and the proofs
+    // that established it. It is domain-separated, so it never equals the key,
+    // a proof, or anything sent on the wire.
+    pub fn session_id(&self) -> [u8; SESSION_ID_LEN] {
+        let digest = D::new()
+            .chain_update(b"apple_srp_client session id")
+            .chain_update(&self.transcript)
+            .chain_update(&*self.key)
+            .finalize();
+        let mut id = [0; SESSION_ID_LEN];
+        id.copy_from_slice(&digest[..SESSION_ID_LEN]);
+        id
+    }
+}
+
+#[cfg(feature = "std")]
+impl<D: Digest + BlockSizeUser> SessionKey<D> {
+    pub fn resumption_secret(&self, lifetime: Duration) -> ResumptionSecret<D> {
+        let mut secret = compute_resumption_secret::<D>(&self.key);
+        let resumption = ResumptionSecret {
+            secret: Zeroizing::new(secret.to_vec()),
+            expires_at: SystemTime::now() + lifetime,
+            d: PhantomData,
+        };
+        secret.as_mut_slice().zeroize();
+        resumption
+    }
+}
+
+#[cfg(feature = "std")]
+impl<D: Digest + BlockSizeUser> ResumptionSecret<D> {
+    pub fn expires_at(&self) -> SystemTime {
+        self.expires_at
+    }
+
+    pub fn is_expired(&self) -> bool {
+        SystemTime::now() >= self.expires_at
+    }
+
+    // Returns the client nonce to send with the verifier's proof; the server
+    // needs it to check M1 and to compute M2 and the key.
+    pub fn respond<R: CryptoRngCore + ?Sized>(
+        &self,
+        challenge: &[u8],
+        rng: &mut R,
+    ) -> Result<([u8; RESUMPTION_NONCE_LEN], SrpClientVerifier<D>), SrpAuthError> {
+        if self.is_expired() {
+            return Err(SrpAuthError::IllegalParameter("resumption_secret"));
+        }
+        if challenge.is_empty() {
+            return Err(SrpAuthError::IllegalParameter("challenge"));
+        }
+
+        let mut client_nonce = [0; RESUMPTION_NONCE_LEN];
+        rng.fill_bytes(&mut client_nonce);
+        let (m1, m2, mut key) =
+            compute_resumption_proofs::<D>(&self.secret, &client_nonce, challenge);
+        let verifier = SrpClientVerifier {
+            m1,
+            m2,
+            key: Zeroizing::new(key.to_vec()),
+            #[cfg(feature = "unsafe-debug")]
+            intermediates: None,
+        };
+        key.as_mut_slice().zeroize();
+        Ok((client_nonce, verifier))
+    }
 }
@@ -0,0 +1,118 @@
+use core::{ptr, slice};
+use std::io;
+
+use zeroize::Zeroize;
+
+use crate::{Credentials, SessionKey};
+
+// A secret in its own mlock()ed pages, so it is never written to
+// swap, with an inaccessible guard page on each side that turns an overrun
+// into a fault instead of a leak. The pages are wiped before being unmapped.
+// Each buffer costs at least three pages and counts against RLIMIT_MEMLOCK.
+// Unix only: the `mlock` feature adds nothing on other targets.
+pub struct LockedBytes {
+    region: *mut u8,
+    region_len: usize,
+    page: usize,
+    len: usize,
+}
+
+// The region is owned exclusively and only ever read through &self.
+unsafe impl Send for LockedBytes {}
+unsafe impl Sync for LockedBytes {}
+
+impl LockedBytes {
+    pub fn new(bytes: &[u8]) -> io::Result<Self> {
+        let page = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+        let page = usize::try_from(page).map_err(|_| io::Error::last_os_error())?;
+        let data_len = bytes.len().div_ceil(page).max(1) * page;
+        let region_len = data_len + 2 * page;
+
+        let region = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                region_len,
+                libc::PROT_NONE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+        if region == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        let locked = Self {
+            region: region.cast(),
+            region_len,
+            page,
+            len: bytes.len(),
+        };
+        let data = locked.data().cast();
+        unsafe {
+            if libc::mprotect(data, data_len, libc::PROT_READ | libc::PROT_WRITE) != 0
+                || libc::mlock(data, data_len) != 0
+            {
+                return Err(io::Error::last_os_error());
+            }
+            ptr::copy_nonoverlapping(bytes.as_ptr(), locked.data(), bytes.len());
+        }
+        Ok(locked)
+    }
+
+    fn data(&self) -> *mut u8 {
+        unsafe { self.region.add(self.page) }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.data(), self.len) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl Drop for LockedBytes {
+    fn drop(&mut self) {
+        let data_len = self.region_len - 2 * self.page;
+        unsafe {
+            // Still PROT_NONE if `new` failed before making it writable.
+            if libc::mprotect(
+                self.data().cast(),
+                data_len,
+                libc::PROT_READ | libc::PROT_WRITE,
+            ) == 0
+            {
+                slice::from_raw_parts_mut(self.data(), data_len).zeroize();
+                libc::munlock(self.data().cast(), data_len);
+            }
+            libc::munmap(self.region.cast(), self.region_len);
+        }
+    }
+}
+
+// Moves the key into locked pages, wiping the heap copy. Keys that stay on
+// the heap after an error are still usable; the call can be retried.
+impl<D: digest::Digest> SessionKey<D> {
+    pub fn lock(&mut self) -> io::Result<()> {
+        self.key.lock()
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.key.is_locked()
+    }
+}
+
+impl<D: digest::Digest> Credentials<D> {
+    pub fn lock(&mut self) -> io::Result<()> {
+        self.x.lock()
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.x.is_locked()
+    }
+}
@@ -0,0 +1,202 @@
+#[cfg(feature = "token")]
+use alloc::string::String;
+use alloc::{vec, vec::Vec};
+
+use crate::SrpAuthError;
+
+const CLIENT_HELLO: u8 = 1;
+const SERVER_CHALLENGE: u8 = 2;
+const CLIENT_PROOF: u8 = 3;
+const SERVER_PROOF: u8 = 4;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientHello {
+    pub username: Vec<u8>,
+    pub a_pub: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerChallenge {
+    pub salt: Vec<u8>,
+    pub b_pub: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientProof {
+    pub m1: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerProof {
+    pub m2: Vec<u8>,
+}
+
+struct Writer {
+    buf: Vec<u8>,
+    name: &'static str,
+}
+
+impl Writer {
+    fn new(tag: u8, name: &'static str) -> Self {
+        Self {
+            buf: vec![tag],
+            name,
+        }
+    }
+
+    // Fields carry a u16 length, so a username or salt over 65535 bytes
+    // cannot be sent.
+    fn field(mut self, value: &[u8]) -> Result<Self, SrpAuthError> {
+        let len =
+            u16::try_from(value.len()).map_err(|_| SrpAuthError::IllegalParameter(self.name))?;
+        self.buf.extend_from_slice(&len.to_be_bytes());
+        self.buf.extend_from_slice(value);
+        Ok(self)
+    }
+
+    fn finish(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    name: &'static str,
+}
+
+impl<'a> Reader<'a> {
+    fn new(buf: &'a [u8], tag: u8, name: &'static str) -> Result<Self, SrpAuthError> {
+        match buf.split_first() {
+            Some((&t, rest)) if t == tag => Ok(Self { buf: rest, name }),
+            _ => Err(SrpAuthError::DecodeError(name)),
+        }
+    }
+
+    fn field(&mut self) -> Result<Vec<u8>, SrpAuthError> {
+        let (len, rest) = self
+            .buf
+            .split_first_chunk::<2>()
+            .ok_or(SrpAuthError::DecodeError(self.name))?;
+        let len = u16::from_be_bytes(*len) as usize;
+        if rest.len() < len {
+            return Err(SrpAuthError::DecodeError(self.name));
+        }
+        let (value, rest) = rest.split_at(len);
+        self.buf = rest;
+        Ok(value.to_vec())
+    }
+
+    fn finish(self) -> Result<(), SrpAuthError> {
+        if self.buf.is_empty() {
+            Ok(())
+        } else {
+            Err(SrpAuthError::DecodeError(self.name))
+        }
+    }
+}
+
+impl ClientHello {
+    pub fn encode(&self) -> Result<Vec<u8>, SrpAuthError> {
+        Ok(Writer::new(CLIENT_HELLO, "client_hello")
+            .field(&self.username)?
+            .field(&self.a_pub)?
+            .finish())
+    }
+
+    pub fn decode(buf: &[u8]) -> Result<Self, SrpAuthError> {
+        let mut r = Reader::new(buf, CLIENT_HELLO, "client_hello")?;
+        let msg = Self {
+            username: r.field()?,
+            a_pub: r.field()?,
+        };
+        r.finish()?;
+        Ok(msg)
+    }
+}
+
+impl ServerChallenge {
+    pub fn encode(&self) -> Result<Vec<u8>, SrpAuthError> {
+        Ok(Writer::new(SERVER_CHALLENGE, "server_challenge")
+            .field(&self.salt)?
+            .field(&self.b_pub)?
+            .finish())
+    }
+
+    pub fn decode(buf: &[u8]) -> Result<Self, SrpAuthError> {
+        let mut r = Reader::new(buf, SERVER_CHALLENGE, "server_challenge")?;
+        let msg = Self {
+            salt: r.field()?,
+            b_pub: r.field()?,
+        };
+        r.finish()?;
+        Ok(msg)
+    }
+}
+
+impl ClientProof {
+    pub fn encode(&self) -> Result<Vec<u8>, SrpAuthError> {
+        Ok(Writer::new(CLIENT_PROOF, "client_proof")
+            .field(&self.m1)?
+            .finish())
+    }
+
+    pub fn decode(buf: &[u8]) -> Result<Self, SrpAuthError> {
+        let mut r = Reader::new(buf, CLIENT_PROOF, "client_proof")?;
+        let msg = Self { m1: r.field()? };
+        r.finish()?;
+        Ok(msg)
+    }
+}
+
+impl ServerProof {
+    pub fn encode(&self) -> Result<Vec<u8>, SrpAuthError> {
+        Ok(Writer::new(SERVER_PROOF, "server_proof")
+            .field(&self.m2)?
+            .finish())
+    }
+
+    pub fn decode(buf: &[u8]) -> Result<Self, SrpAuthError> {
+        let mut r = Reader::new(buf, SERVER_PROOF, "server_proof")?;
+        let msg = Self { m2: r.field()? };
+        r.finish()?;
+        Ok(msg)
+    }
+}
+
+#[cfg(feature = "token")]
+pub const TOKEN_VERSION: u8 = 1;
+
+#[cfg(feature = "token")]
+macro_rules! impl_token {
+    ($($ty:ident => $name:literal),* $(,)?) => {$(
+        impl $ty {
+            pub fn to_token(&self) -> Result<String, SrpAuthError> {
+                use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+
+                let mut buf = vec![TOKEN_VERSION];
+                buf.extend_from_slice(&self.encode()?);
+                Ok(URL_SAFE_NO_PAD.encode(buf))
+            }
+
+            pub fn from_token(token: &str) -> Result<Self, SrpAuthError> {
+                use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+
+                let buf = URL_SAFE_NO_PAD
+                    .decode(token)
+                    .map_err(|_| SrpAuthError::DecodeError($name))?;
+                match buf.split_first() {
+                    Some((&TOKEN_VERSION, msg)) => Self::decode(msg),
+                    _ => Err(SrpAuthError::DecodeError($name)),
+                }
+            }
+        }
+    )*};
+}
+
+#[cfg(feature = "token")]
+impl_token! {
+    ClientHello => "client_hello",
+    ServerChallenge => "server_challenge",
+    ClientProof => "client_proof",
+    ServerProof => "server_proof",
+}
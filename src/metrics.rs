@@ -0,0 +1,125 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::PakeClient;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandshakeStep {
+    // Computing A = g^a.
+    Start,
+    // Computing the premaster secret S.
+    ProcessChallenge,
+}
+
+// Callbacks for exporting handshake counters and latencies, e.g. to
+// Prometheus. Every method defaults to doing nothing.
+pub trait Metrics {
+    fn handshake_started(&self) {}
+
+    fn handshake_succeeded(&self) {}
+
+    fn handshake_failed(&self) {}
+
+    // Wall time of a step dominated by modular exponentiation, reported
+    // whether or not the step succeeded.
+    fn modpow_duration(&self, _step: HandshakeStep, _elapsed: Duration) {}
+}
+
+impl<M: Metrics + ?Sized> Metrics for &M {
+    fn handshake_started(&self) {
+        (**self).handshake_started()
+    }
+
+    fn handshake_succeeded(&self) {
+        (**self).handshake_succeeded()
+    }
+
+    fn handshake_failed(&self) {
+        (**self).handshake_failed()
+    }
+
+    fn modpow_duration(&self, step: HandshakeStep, elapsed: Duration) {
+        (**self).modpow_duration(step, elapsed)
+    }
+}
+
+impl<M: Metrics + ?Sized> Metrics for Arc<M> {
+    fn handshake_started(&self) {
+        (**self).handshake_started()
+    }
+
+    fn handshake_succeeded(&self) {
+        (**self).handshake_succeeded()
+    }
+
+    fn handshake_failed(&self) {
+        (**self).handshake_failed()
+    }
+
+    fn modpow_duration(&self, step: HandshakeStep, elapsed: Duration) {
+        (**self).modpow_duration(step, elapsed)
+    }
+}
+
+// Wraps a `PakeClient` and reports each handshake to `metrics`. A handshake
+// counts as failed as soon as any step returns an error.
+pub struct MeteredClient<P, M> {
+    inner: P,
+    metrics: M,
+}
+
+impl<P, M> MeteredClient<P, M> {
+    pub fn new(inner: P, metrics: M) -> Self {
+        Self { inner, metrics }
+    }
+
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P: PakeClient, M: Metrics> MeteredClient<P, M> {
+    fn observe<T>(&self, result: Result<T, P::Error>) -> Result<T, P::Error> {
+        if result.is_err() {
+            self.metrics.handshake_failed();
+        }
+        result
+    }
+}
+
+impl<P: PakeClient, M: Metrics> PakeClient for MeteredClient<P, M> {
+    type Started = P::Started;
+    type Confirming = P::Confirming;
+    type Key = P::Key;
+    type Error = P::Error;
+
+    fn start(&self, username: &[u8], ephemeral: &[u8]) -> Result<(Vec<u8>, P::Started), P::Error> {
+        self.metrics.handshake_started();
+        let started = Instant::now();
+        let result = self.inner.start(username, ephemeral);
+        self.metrics
+            .modpow_duration(HandshakeStep::Start, started.elapsed());
+        self.observe(result)
+    }
+
+    fn process_challenge(
+        &self,
+        state: P::Started,
+        password: &[u8],
+        challenge: &[u8],
+    ) -> Result<(Vec<u8>, P::Confirming), P::Error> {
+        let started = Instant::now();
+        let result = self.inner.process_challenge(state, password, challenge);
+        self.metrics
+            .modpow_duration(HandshakeStep::ProcessChallenge, started.elapsed());
+        self.observe(result)
+    }
+
+    fn finish(&self, state: P::Confirming, server_proof: &[u8]) -> Result<P::Key, P::Error> {
+        let key = self.observe(self.inner.finish(state, server_proof))?;
+        self.metrics.handshake_succeeded();
+        Ok(key)
+    }
+}
@@ -0,0 +1,97 @@
+use alloc::vec::Vec;
+
+use crate::{
+    ClientHello, ClientProof, PakeClient, ServerChallenge, ServerProof, SrpAuthError,
+    constant_time_eq,
+};
+
+pub const MOCK_A_PUB: [u8; 32] = [0xaa; 32];
+pub const MOCK_B_PUB: [u8; 32] = [0xbb; 32];
+pub const MOCK_SALT: [u8; 16] = [0x5a; 16];
+pub const MOCK_M1: [u8; 32] = [0x11; 32];
+pub const MOCK_M2: [u8; 32] = [0x22; 32];
+pub const MOCK_SESSION_KEY: [u8; 32] = [0x4b; 32];
+
+// Speaks the same wire messages as `SrpClient` without any cryptography or
+// RNG: A and M1 are fixed, and any B or M2 other than the scripted ones is
+// rejected with the same errors the real client returns.
+#[derive(Debug, Clone)]
+pub struct MockSrpClient {
+    pub a_pub: Vec<u8>,
+    pub b_pub: Vec<u8>,
+    pub m1: Vec<u8>,
+    pub m2: Vec<u8>,
+    pub session_key: Vec<u8>,
+}
+
+impl Default for MockSrpClient {
+    fn default() -> Self {
+        Self {
+            a_pub: MOCK_A_PUB.to_vec(),
+            b_pub: MOCK_B_PUB.to_vec(),
+            m1: MOCK_M1.to_vec(),
+            m2: MOCK_M2.to_vec(),
+            session_key: MOCK_SESSION_KEY.to_vec(),
+        }
+    }
+}
+
+impl MockSrpClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn expected_challenge(&self) -> Result<Vec<u8>, SrpAuthError> {
+        ServerChallenge {
+            salt: MOCK_SALT.to_vec(),
+            b_pub: self.b_pub.clone(),
+        }
+        .encode()
+    }
+
+    pub fn expected_server_proof(&self) -> Result<Vec<u8>, SrpAuthError> {
+        ServerProof {
+            m2: self.m2.clone(),
+        }
+        .encode()
+    }
+}
+
+impl PakeClient for MockSrpClient {
+    type Started = ();
+    type Confirming = ();
+    type Key = Vec<u8>;
+    type Error = SrpAuthError;
+
+    fn start(&self, username: &[u8], _ephemeral: &[u8]) -> Result<(Vec<u8>, ()), SrpAuthError> {
+        let hello = ClientHello {
+            username: username.to_vec(),
+            a_pub: self.a_pub.clone(),
+        };
+        Ok((hello.encode()?, ()))
+    }
+
+    fn process_challenge(
+        &self,
+        _state: (),
+        _password: &[u8],
+        challenge: &[u8],
+    ) -> Result<(Vec<u8>, ()), SrpAuthError> {
+        let challenge = ServerChallenge::decode(challenge)?;
+        if !constant_time_eq(&challenge.b_pub, &self.b_pub) {
+            return Err(SrpAuthError::IllegalParameter("b_pub"));
+        }
+        let proof = ClientProof {
+            m1: self.m1.clone(),
+        };
+        Ok((proof.encode()?, ()))
+    }
+
+    fn finish(&self, _state: (), server_proof: &[u8]) -> Result<Vec<u8>, SrpAuthError> {
+        let server_proof = ServerProof::decode(server_proof)?;
+        if !constant_time_eq(&server_proof.m2, &self.m2) {
+            return Err(SrpAuthError::BadRecordMac("server"));
+        }
+        Ok(self.session_key.clone())
+    }
+}
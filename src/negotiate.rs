@@ -0,0 +1,38 @@
+use crate::{SrpAuthError, SrpGroup};
+
+pub enum GroupOutcome<T> {
+    Accepted(T),
+    Rejected,
+}
+
+pub struct GroupPolicy<'a> {
+    pub groups: &'a [&'a SrpGroup],
+    pub min_bits: u64,
+}
+
+impl<'a> GroupPolicy<'a> {
+    pub fn new(groups: &'a [&'a SrpGroup]) -> Self {
+        Self {
+            groups,
+            min_bits: 2048,
+        }
+    }
+
+    pub fn allows(&self, params: &SrpGroup) -> bool {
+        params.n.bits() >= self.min_bits
+    }
+
+    pub fn negotiate<T, E, F>(&self, mut attempt: F) -> Result<(&'a SrpGroup, T), E>
+    where
+        F: FnMut(&'a SrpGroup) -> Result<GroupOutcome<T>, E>,
+        E: From<SrpAuthError>,
+    {
+        for &params in self.groups.iter().filter(|params| self.allows(params)) {
+            match attempt(params)? {
+                GroupOutcome::Accepted(value) => return Ok((params, value)),
+                GroupOutcome::Rejected => continue,
+            }
+        }
+        Err(SrpAuthError::IllegalParameter("group").into())
+    }
+}
@@ -0,0 +1,104 @@
+use napi::{Error, Result, bindgen_prelude::Buffer};
+use napi_derive::napi;
+
+use crate::{CompatProfile, DigestAlgorithm, DynSrpClient, DynSrpClientVerifier, SrpAuthError};
+
+fn to_napi(err: SrpAuthError) -> Error {
+    Error::from_reason(err.to_string())
+}
+
+fn client(profile: Option<String>, digest: Option<String>) -> Result<DynSrpClient<'static>> {
+    let profile = match profile {
+        Some(name) => CompatProfile::from_name(&name)
+            .ok_or_else(|| Error::from_reason(format!("unknown profile '{name}'")))?,
+        None => CompatProfile::default(),
+    };
+    let algorithm = match digest {
+        Some(name) => DigestAlgorithm::from_name(&name)
+            .ok_or_else(|| Error::from_reason(format!("unknown digest '{name}'")))?,
+        None => profile.digest(),
+    };
+    Ok(DynSrpClient::with_options(
+        profile.group(),
+        algorithm,
+        profile.options(),
+    ))
+}
+
+// `new SrpClient(profile?, digest?)` from JavaScript; every byte argument and
+// result is a Buffer and every SrpAuthError becomes a thrown Error.
+#[napi(js_name = "SrpClient")]
+pub struct NodeSrpClient {
+    inner: DynSrpClient<'static>,
+}
+
+#[napi]
+impl NodeSrpClient {
+    #[napi(constructor)]
+    pub fn new(profile: Option<String>, digest: Option<String>) -> Result<Self> {
+        Ok(Self {
+            inner: client(profile, digest)?,
+        })
+    }
+
+    #[napi]
+    pub fn compute_verifier(&self, username: Buffer, password: Buffer, salt: Buffer) -> Buffer {
+        self.inner
+            .compute_verifier(&username, &password, &salt)
+            .into()
+    }
+
+    #[napi]
+    pub fn compute_public_ephemeral(&self, a: Buffer) -> Result<Buffer> {
+        Ok(self
+            .inner
+            .compute_public_ephemeral(&a)
+            .map_err(to_napi)?
+            .into())
+    }
+
+    #[napi]
+    pub fn process_reply(
+        &self,
+        a: Buffer,
+        username: Buffer,
+        password: Buffer,
+        salt: Buffer,
+        b_pub: Buffer,
+    ) -> Result<NodeSrpVerifier> {
+        let verifier = self
+            .inner
+            .process_reply(&a, &username, &password, &salt, &b_pub)
+            .map_err(to_napi)?;
+        Ok(NodeSrpVerifier {
+            inner: Some(verifier),
+        })
+    }
+}
+
+#[napi(js_name = "SrpVerifier")]
+pub struct NodeSrpVerifier {
+    inner: Option<DynSrpClientVerifier>,
+}
+
+#[napi]
+impl NodeSrpVerifier {
+    #[napi]
+    pub fn proof(&self) -> Result<Buffer> {
+        let verifier = self.inner.as_ref().ok_or_else(consumed)?;
+        Ok(verifier.proof().to_vec().into())
+    }
+
+    // Returns the session key; the verifier cannot be used again afterwards,
+    // whether or not the server proof was accepted.
+    #[napi]
+    pub fn verify_server(&mut self, m2: Buffer) -> Result<Buffer> {
+        let verifier = self.inner.take().ok_or_else(consumed)?;
+        let key = verifier.verify_server(&m2).map_err(to_napi)?;
+        Ok(key.as_bytes().to_vec().into())
+    }
+}
+
+fn consumed() -> Error {
+    Error::from_reason("verifier already consumed")
+}
@@ -0,0 +1,168 @@
+use std::fmt;
+
+use digest::Digest;
+use os_keyring::Entry;
+use zeroize::Zeroizing;
+
+use crate::{
+    CredentialStore, Credentials, LongTermKeys, MemoryPairingStore, Pairing, PairingStore,
+    encode_hex,
+    pairing_json::{decode_store, encode_store},
+};
+
+#[derive(Debug)]
+pub enum KeychainError {
+    Os(os_keyring::Error),
+    Malformed,
+}
+
+impl fmt::Display for KeychainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeychainError::Os(err) => write!(f, "keychain error: {err}"),
+            KeychainError::Malformed => write!(f, "keychain item is malformed"),
+        }
+    }
+}
+
+impl std::error::Error for KeychainError {}
+
+impl From<os_keyring::Error> for KeychainError {
+    fn from(err: os_keyring::Error) -> Self {
+        KeychainError::Os(err)
+    }
+}
+
+// Keeps the pairing keyring as a single generic secret in the platform store:
+// the login Keychain on macOS and iOS, Credential Manager on Windows and the
+// Secret Service (GNOME Keyring, KWallet) on Linux, which needs libdbus at
+// build time unless `vendored-dbus` is on. The secret is the same JSON
+// `FileKeyring` encrypts, so it is protected by the OS rather than by a
+// passphrase. Every change rewrites the whole item.
+//
+// Derived credentials get one item per username next to it, named
+// "<account>.credentials.<hex username>", and are read straight from the
+// platform store rather than kept in memory.
+pub struct OsKeychain {
+    service: String,
+    account: String,
+    entry: Entry,
+    store: MemoryPairingStore,
+}
+
+impl OsKeychain {
+    pub fn open(service: &str, account: &str) -> Result<Self, KeychainError> {
+        let entry = Entry::new(service, account)?;
+        let store = match entry.get_secret() {
+            Ok(secret) => decode_store(&Zeroizing::new(secret)).ok_or(KeychainError::Malformed)?,
+            Err(os_keyring::Error::NoEntry) => MemoryPairingStore::new(),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(Self {
+            service: service.to_owned(),
+            account: account.to_owned(),
+            entry,
+            store,
+        })
+    }
+
+    fn credentials_entry(&self, username: &[u8]) -> Result<Entry, KeychainError> {
+        let account = format!("{}.credentials.{}", self.account, encode_hex(username));
+        Ok(Entry::new(&self.service, &account)?)
+    }
+
+    // Removes the pairing item from the platform store, forgetting every key
+    // and pairing. Credentials items are not enumerable, so remove them by
+    // username first.
+    pub fn delete(self) -> Result<(), KeychainError> {
+        match self.entry.delete_credential() {
+            Ok(()) | Err(os_keyring::Error::NoEntry) => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    // As in `FileKeyring`, memory only changes once the platform store has
+    // accepted the new contents.
+    fn update(
+        &mut self,
+        change: impl FnOnce(&mut MemoryPairingStore),
+    ) -> Result<(), KeychainError> {
+        let mut store = self.store.clone();
+        change(&mut store);
+        self.entry.set_secret(&encode_store(&store))?;
+        self.store = store;
+        Ok(())
+    }
+}
+
+impl PairingStore for OsKeychain {
+    type Error = KeychainError;
+
+    fn load_keys(&self) -> Result<Option<LongTermKeys>, KeychainError> {
+        let Ok(keys) = self.store.load_keys();
+        Ok(keys)
+    }
+
+    fn save_keys(&mut self, keys: &LongTermKeys) -> Result<(), KeychainError> {
+        self.update(|store| {
+            let Ok(()) = store.save_keys(keys);
+        })
+    }
+
+    fn load_pairing(&self, identifier: &[u8]) -> Result<Option<Pairing>, KeychainError> {
+        let Ok(pairing) = self.store.load_pairing(identifier);
+        Ok(pairing)
+    }
+
+    fn save_pairing(&mut self, pairing: &Pairing) -> Result<(), KeychainError> {
+        self.update(|store| {
+            let Ok(()) = store.save_pairing(pairing);
+        })
+    }
+
+    fn remove_pairing(&mut self, identifier: &[u8]) -> Result<(), KeychainError> {
+        self.update(|store| {
+            let Ok(()) = store.remove_pairing(identifier);
+        })
+    }
+
+    fn list_pairings(&self) -> Result<Vec<Pairing>, KeychainError> {
+        let Ok(pairings) = self.store.list_pairings();
+        Ok(pairings)
+    }
+}
+
+impl<D: Digest> CredentialStore<D> for OsKeychain {
+    type Error = KeychainError;
+
+    fn load_credentials(
+        &self,
+        username: &[u8],
+        salt: &[u8],
+    ) -> Result<Option<Credentials<D>>, KeychainError> {
+        let secret = match self.credentials_entry(username)?.get_secret() {
+            Ok(secret) => Zeroizing::new(secret),
+            Err(os_keyring::Error::NoEntry) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let credentials = Credentials::from_bytes(&secret).ok_or(KeychainError::Malformed)?;
+        Ok(Some(credentials).filter(|credentials| credentials.salt() == salt))
+    }
+
+    fn save_credentials(
+        &mut self,
+        username: &[u8],
+        credentials: &Credentials<D>,
+    ) -> Result<(), KeychainError> {
+        self.credentials_entry(username)?
+            .set_secret(&credentials.to_bytes())?;
+        Ok(())
+    }
+
+    fn remove_credentials(&mut self, username: &[u8]) -> Result<(), KeychainError> {
+        match self.credentials_entry(username)?.delete_credential() {
+            Ok(()) | Err(os_keyring::Error::NoEntry) => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
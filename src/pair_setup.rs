@@ -0,0 +1,184 @@
+use alloc::{string::ToString, vec::Vec};
+
+use digest::Digest;
+use rand_core::CryptoRngCore;
+use zeroize::Zeroizing;
+
+#[cfg(feature = "std")]
+use crate::{AttemptDecision, TLV_RETRY_DELAY};
+use crate::{
+    HapError, SessionKey, SetupCode, SrpAuthError, SrpClient, SrpGroup, SrpOptions, SrpServer,
+    TLV_ERROR, TLV_METHOD, TLV_PROOF, TLV_PUBLIC_KEY, TLV_SALT, TLV_STATE, decode_tlv8,
+    encode_tlv8,
+};
+
+// The SRP identity every Pair-Setup uses; the setup code is the password.
+pub const PAIR_SETUP_USERNAME: &[u8] = b"Pair-Setup";
+
+const METHOD_PAIR_SETUP: u8 = 0;
+const METHOD_PAIR_SETUP_WITH_AUTH: u8 = 1;
+
+pub const PAIR_SETUP_M1: u8 = 1;
+pub const PAIR_SETUP_M2: u8 = 2;
+pub const PAIR_SETUP_M3: u8 = 3;
+pub const PAIR_SETUP_M4: u8 = 4;
+
+// SRP-6a with the username hashed into x. Use it with `G_3072` and SHA-512,
+// as HAP specifies.
+pub fn pair_setup_options() -> SrpOptions {
+    SrpOptions {
+        username_in_x: true,
+        ..SrpOptions::default()
+    }
+}
+
+// The accessory's half of Pair-Setup M1 to M4: it answers the controller's
+// request with a salt and B, then checks the controller's proof and answers
+// with its own. M5 and M6, the Ed25519 exchange under the derived key, are
+// left to the caller. Throttling is too: consult an `AttemptTracker` before
+// `respond_m1` and record every `Rejected` against it.
+pub struct PairSetupResponder<'a, D: Digest> {
+    server: SrpServer<'a, D>,
+    salt: Vec<u8>,
+    verifier: Vec<u8>,
+}
+
+// The private ephemeral behind the B sent in M2, needed again for M3.
+pub struct PairSetupChallenge {
+    b: Zeroizing<Vec<u8>>,
+}
+
+pub enum PairSetupOutcome<D: Digest> {
+    // M4 carrying the accessory's proof, and the key M5 is encrypted under.
+    Paired {
+        response: Vec<u8>,
+        key: SessionKey<D>,
+    },
+    // M4 carrying the Authentication error: the controller's proof was for a
+    // different setup code.
+    Rejected {
+        response: Vec<u8>,
+    },
+}
+
+impl<'a, D: Digest> PairSetupResponder<'a, D> {
+    // `salt` and `verifier` as produced by `from_setup_code`, e.g. at the
+    // factory, so the setup code itself never has to be stored.
+    pub fn new(params: &'a SrpGroup, salt: &[u8], verifier: &[u8]) -> Self {
+        Self {
+            server: SrpServer::with_options(params, pair_setup_options()),
+            salt: salt.to_vec(),
+            verifier: verifier.to_vec(),
+        }
+    }
+
+    pub fn from_setup_code<R: CryptoRngCore + ?Sized>(
+        params: &'a SrpGroup,
+        code: SetupCode,
+        rng: &mut R,
+    ) -> Self {
+        let client = SrpClient::<D>::with_options(params, pair_setup_options());
+        let registration = client.register(PAIR_SETUP_USERNAME, code.to_string().as_bytes(), rng);
+        Self::new(params, &registration.salt, &registration.verifier)
+    }
+
+    pub fn salt(&self) -> &[u8] {
+        &self.salt
+    }
+
+    pub fn verifier(&self) -> &[u8] {
+        &self.verifier
+    }
+
+    // Checks M1 and returns M2 with the salt and B. `ephemeral` is the
+    // accessory's fresh private b, as `PakeClient::start` takes a.
+    pub fn respond_m1(
+        &self,
+        request: &[u8],
+        ephemeral: &[u8],
+    ) -> Result<(Vec<u8>, PairSetupChallenge), SrpAuthError> {
+        const ERR: SrpAuthError = SrpAuthError::DecodeError("pair_setup_m1");
+
+        let items = decode_tlv8(request)?;
+        if field(&items, TLV_STATE) != Some(&[PAIR_SETUP_M1]) {
+            return Err(ERR);
+        }
+        match field(&items, TLV_METHOD) {
+            Some(&[METHOD_PAIR_SETUP | METHOD_PAIR_SETUP_WITH_AUTH]) => {}
+            _ => return Err(ERR),
+        }
+
+        let b_pub = self
+            .server
+            .compute_public_ephemeral(ephemeral, &self.verifier)?;
+        let response = encode_tlv8(&[
+            (TLV_STATE, &[PAIR_SETUP_M2]),
+            (TLV_PUBLIC_KEY, &b_pub),
+            (TLV_SALT, &self.salt),
+        ]);
+        let challenge = PairSetupChallenge {
+            b: Zeroizing::new(ephemeral.to_vec()),
+        };
+        Ok((response, challenge))
+    }
+
+    // Checks the controller's A and proof from M3. A malformed message or an
+    // invalid A is an error; a well-formed proof that does not match is a
+    // `Rejected` outcome with the M4 to send.
+    pub fn respond_m3(
+        &self,
+        challenge: PairSetupChallenge,
+        request: &[u8],
+    ) -> Result<PairSetupOutcome<D>, SrpAuthError> {
+        const ERR: SrpAuthError = SrpAuthError::DecodeError("pair_setup_m3");
+
+        let items = decode_tlv8(request)?;
+        if field(&items, TLV_STATE) != Some(&[PAIR_SETUP_M3]) {
+            return Err(ERR);
+        }
+        let a_pub = field(&items, TLV_PUBLIC_KEY).ok_or(ERR)?;
+        let proof = field(&items, TLV_PROOF).ok_or(ERR)?;
+
+        let verifier = self.server.process_hello(
+            &challenge.b,
+            PAIR_SETUP_USERNAME,
+            &self.salt,
+            &self.verifier,
+            a_pub,
+        )?;
+        match verifier.verify_client(proof) {
+            Ok((m2, key)) => Ok(PairSetupOutcome::Paired {
+                response: encode_tlv8(&[(TLV_STATE, &[PAIR_SETUP_M4]), (TLV_PROOF, &m2)]),
+                key,
+            }),
+            Err(_) => Ok(PairSetupOutcome::Rejected {
+                response: error_response(PAIR_SETUP_M4, HapError::Authentication),
+            }),
+        }
+    }
+}
+
+// A Pair-Setup reply carrying only `error`, for `state` M2 or M4.
+pub fn error_response(state: u8, error: HapError) -> Vec<u8> {
+    encode_tlv8(&[(TLV_STATE, &[state]), (TLV_ERROR, &[error.code()])])
+}
+
+// The M2 to send instead of a challenge when `decision` refuses the attempt,
+// with the retry delay in seconds for a backoff.
+#[cfg(feature = "std")]
+pub fn throttled_response(decision: AttemptDecision) -> Option<Vec<u8>> {
+    let error = decision.error()?;
+    let mut response = error_response(PAIR_SETUP_M2, error);
+    if let AttemptDecision::Backoff(delay) = decision {
+        let seconds = delay.as_secs().max(1).min(u16::MAX.into()) as u16;
+        response.extend(encode_tlv8(&[(TLV_RETRY_DELAY, &seconds.to_le_bytes())]));
+    }
+    Some(response)
+}
+
+fn field(items: &[(u8, Vec<u8>)], ty: u8) -> Option<&[u8]> {
+    items
+        .iter()
+        .find(|(item_ty, _)| *item_ty == ty)
+        .map(|(_, value)| value.as_slice())
+}
@@ -0,0 +1,218 @@
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::convert::Infallible;
+
+use zeroize::Zeroizing;
+
+use crate::{
+    SrpAuthError, TLV_ERROR, TLV_IDENTIFIER, TLV_METHOD, TLV_PERMISSIONS, TLV_PUBLIC_KEY,
+    TLV_SEPARATOR, TLV_STATE, decode_tlv8, encode_tlv8,
+};
+
+const METHOD_ADD_PAIRING: u8 = 3;
+const METHOD_REMOVE_PAIRING: u8 = 4;
+const METHOD_LIST_PAIRINGS: u8 = 5;
+
+const STATE_M1: u8 = 1;
+const STATE_M2: u8 = 2;
+
+pub const PERMISSION_USER: u8 = 0;
+pub const PERMISSION_ADMIN: u8 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HapError {
+    Unknown,
+    Authentication,
+    Backoff,
+    MaxPeers,
+    MaxTries,
+    Unavailable,
+    Busy,
+    Other(u8),
+}
+
+impl HapError {
+    pub fn from_code(code: u8) -> Self {
+        match code {
+            1 => HapError::Unknown,
+            2 => HapError::Authentication,
+            3 => HapError::Backoff,
+            4 => HapError::MaxPeers,
+            5 => HapError::MaxTries,
+            6 => HapError::Unavailable,
+            7 => HapError::Busy,
+            code => HapError::Other(code),
+        }
+    }
+
+    pub fn code(self) -> u8 {
+        match self {
+            HapError::Unknown => 1,
+            HapError::Authentication => 2,
+            HapError::Backoff => 3,
+            HapError::MaxPeers => 4,
+            HapError::MaxTries => 5,
+            HapError::Unavailable => 6,
+            HapError::Busy => 7,
+            HapError::Other(code) => code,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pairing {
+    pub identifier: Vec<u8>,
+    pub public_key: Vec<u8>,
+    pub permissions: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PairingsRequest {
+    Add(Pairing),
+    Remove { identifier: Vec<u8> },
+    List,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PairingsResponse {
+    Done,
+    List(Vec<Pairing>),
+    Error(HapError),
+}
+
+// These are the plaintext bodies of the Pairings characteristic; the caller
+// sends them over an already established encrypted session.
+impl PairingsRequest {
+    pub fn encode(&self) -> Vec<u8> {
+        match self {
+            PairingsRequest::Add(pairing) => encode_tlv8(&[
+                (TLV_STATE, &[STATE_M1]),
+                (TLV_METHOD, &[METHOD_ADD_PAIRING]),
+                (TLV_IDENTIFIER, &pairing.identifier),
+                (TLV_PUBLIC_KEY, &pairing.public_key),
+                (TLV_PERMISSIONS, &[pairing.permissions]),
+            ]),
+            PairingsRequest::Remove { identifier } => encode_tlv8(&[
+                (TLV_STATE, &[STATE_M1]),
+                (TLV_METHOD, &[METHOD_REMOVE_PAIRING]),
+                (TLV_IDENTIFIER, identifier),
+            ]),
+            PairingsRequest::List => encode_tlv8(&[
+                (TLV_STATE, &[STATE_M1]),
+                (TLV_METHOD, &[METHOD_LIST_PAIRINGS]),
+            ]),
+        }
+    }
+}
+
+impl PairingsResponse {
+    pub fn decode(buf: &[u8]) -> Result<Self, SrpAuthError> {
+        const ERR: SrpAuthError = SrpAuthError::DecodeError("pairings");
+
+        let items = decode_tlv8(buf)?;
+        let mut items = items.iter().map(|(ty, value)| (*ty, value.as_slice()));
+        if items.next() != Some((TLV_STATE, &[STATE_M2][..])) {
+            return Err(ERR);
+        }
+
+        let mut pairings = Vec::new();
+        let mut current = None;
+        for (ty, value) in items {
+            match (ty, value) {
+                (TLV_ERROR, &[code]) => {
+                    return Ok(PairingsResponse::Error(HapError::from_code(code)));
+                }
+                (TLV_IDENTIFIER, identifier) if current.is_none() => {
+                    current = Some(Pairing {
+                        identifier: identifier.to_vec(),
+                        public_key: Vec::new(),
+                        permissions: PERMISSION_USER,
+                    });
+                }
+                (TLV_PUBLIC_KEY, public_key) => {
+                    current.as_mut().ok_or(ERR)?.public_key = public_key.to_vec();
+                }
+                (TLV_PERMISSIONS, &[permissions]) => {
+                    current.as_mut().ok_or(ERR)?.permissions = permissions;
+                }
+                (TLV_SEPARATOR, []) => pairings.push(current.take().ok_or(ERR)?),
+                _ => return Err(ERR),
+            }
+        }
+        pairings.extend(current);
+
+        if pairings.is_empty() {
+            Ok(PairingsResponse::Done)
+        } else {
+            Ok(PairingsResponse::List(pairings))
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct LongTermKeys {
+    pub identifier: Vec<u8>,
+    pub public_key: Vec<u8>,
+    pub secret_key: Zeroizing<Vec<u8>>,
+}
+
+// Persistence for the controller's own Ed25519 identity and the accessories it
+// is paired with, keyed by accessory pairing ID.
+pub trait PairingStore {
+    type Error;
+
+    fn load_keys(&self) -> Result<Option<LongTermKeys>, Self::Error>;
+
+    fn save_keys(&mut self, keys: &LongTermKeys) -> Result<(), Self::Error>;
+
+    fn load_pairing(&self, identifier: &[u8]) -> Result<Option<Pairing>, Self::Error>;
+
+    fn save_pairing(&mut self, pairing: &Pairing) -> Result<(), Self::Error>;
+
+    fn remove_pairing(&mut self, identifier: &[u8]) -> Result<(), Self::Error>;
+
+    fn list_pairings(&self) -> Result<Vec<Pairing>, Self::Error>;
+}
+
+#[derive(Clone, Default)]
+pub struct MemoryPairingStore {
+    keys: Option<LongTermKeys>,
+    pairings: BTreeMap<Vec<u8>, Pairing>,
+}
+
+impl MemoryPairingStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PairingStore for MemoryPairingStore {
+    type Error = Infallible;
+
+    fn load_keys(&self) -> Result<Option<LongTermKeys>, Infallible> {
+        Ok(self.keys.clone())
+    }
+
+    fn save_keys(&mut self, keys: &LongTermKeys) -> Result<(), Infallible> {
+        self.keys = Some(keys.clone());
+        Ok(())
+    }
+
+    fn load_pairing(&self, identifier: &[u8]) -> Result<Option<Pairing>, Infallible> {
+        Ok(self.pairings.get(identifier).cloned())
+    }
+
+    fn save_pairing(&mut self, pairing: &Pairing) -> Result<(), Infallible> {
+        self.pairings
+            .insert(pairing.identifier.clone(), pairing.clone());
+        Ok(())
+    }
+
+    fn remove_pairing(&mut self, identifier: &[u8]) -> Result<(), Infallible> {
+        self.pairings.remove(identifier);
+        Ok(())
+    }
+
+    fn list_pairings(&self) -> Result<Vec<Pairing>, Infallible> {
+        Ok(self.pairings.values().cloned().collect())
+    }
+}
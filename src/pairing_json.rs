@@ -0,0 +1,85 @@
+use std::io;
+
+use serde_json::{Map, Value, json};
+use zeroize::Zeroizing;
+
+use crate::{LongTermKeys, MemoryPairingStore, Pairing, PairingStore, decode_hex, encode_hex};
+
+// A whole `MemoryPairingStore` as plaintext JSON, shared by the backends that
+// persist it in one piece: `FileKeyring` encrypts it, `OsKeychain` hands it
+// to the platform store. The hex secret key is moved into the `Value` rather
+// than passed through `json!`, which would serialize a copy, and is taken back
+// out to be wiped. The output buffer is sized up front so growing it leaves no
+// stale copy of the plaintext behind.
+pub(crate) fn encode_store(store: &MemoryPairingStore) -> Zeroizing<Vec<u8>> {
+    let Ok(keys) = store.load_keys();
+    let Ok(pairings) = store.list_pairings();
+    let mut contents = json!({
+        "keys": null,
+        "pairings": pairings.iter().map(|pairing| json!({
+            "identifier": encode_hex(&pairing.identifier),
+            "public_key": encode_hex(&pairing.public_key),
+            "permissions": pairing.permissions,
+        })).collect::<Vec<_>>(),
+    });
+    if let Some(keys) = keys {
+        contents["keys"] = Value::Object(Map::from_iter([
+            ("identifier".into(), encode_hex(&keys.identifier).into()),
+            ("public_key".into(), encode_hex(&keys.public_key).into()),
+            ("secret_key".into(), encode_hex(&keys.secret_key).into()),
+        ]));
+    }
+    let mut len = ByteCount(0);
+    serde_json::to_writer(&mut len, &contents).expect("keyring is valid JSON");
+    let mut out = Zeroizing::new(Vec::with_capacity(len.0));
+    serde_json::to_writer(&mut *out, &contents).expect("keyring is valid JSON");
+    take_secret(&mut contents);
+    out
+}
+
+pub(crate) fn decode_store(bytes: &[u8]) -> Option<MemoryPairingStore> {
+    let mut contents: Value = serde_json::from_slice(bytes).ok()?;
+    let secret_key = take_secret(&mut contents);
+    let mut store = MemoryPairingStore::new();
+    if !contents["keys"].is_null() {
+        let keys = &contents["keys"];
+        let Ok(()) = store.save_keys(&LongTermKeys {
+            identifier: hex_field(&keys["identifier"])?,
+            public_key: hex_field(&keys["public_key"])?,
+            secret_key: Zeroizing::new(decode_hex(secret_key.as_deref()?).ok()?),
+        });
+    }
+    for pairing in contents["pairings"].as_array()? {
+        let permissions = u8::try_from(pairing["permissions"].as_u64()?).ok()?;
+        let Ok(()) = store.save_pairing(&Pairing {
+            identifier: hex_field(&pairing["identifier"])?,
+            public_key: hex_field(&pairing["public_key"])?,
+            permissions,
+        });
+    }
+    Some(store)
+}
+
+fn take_secret(contents: &mut Value) -> Option<Zeroizing<String>> {
+    match contents.pointer_mut("/keys/secret_key").map(Value::take) {
+        Some(Value::String(hex)) => Some(Zeroizing::new(hex)),
+        _ => None,
+    }
+}
+
+struct ByteCount(usize);
+
+impl io::Write for ByteCount {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn hex_field(value: &Value) -> Option<Vec<u8>> {
+    decode_hex(value.as_str()?).ok()
+}
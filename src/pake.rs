@@ -0,0 +1,92 @@
+use alloc::vec::Vec;
+
+use digest::Digest;
+use zeroize::Zeroizing;
+
+use crate::{
+    ClientHello, ClientProof, ServerChallenge, ServerProof, SessionKey, SrpAuthError, SrpClient,
+    SrpClientVerifier,
+};
+
+pub trait PakeClient {
+    type Started;
+    type Confirming;
+    type Key;
+    type Error;
+
+    fn start(
+        &self,
+        username: &[u8],
+        ephemeral: &[u8],
+    ) -> Result<(Vec<u8>, Self::Started), Self::Error>;
+
+    fn process_challenge(
+        &self,
+        state: Self::Started,
+        password: &[u8],
+        challenge: &[u8],
+    ) -> Result<(Vec<u8>, Self::Confirming), Self::Error>;
+
+    fn finish(
+        &self,
+        state: Self::Confirming,
+        server_proof: &[u8],
+    ) -> Result<Self::Key, Self::Error>;
+}
+
+pub struct SrpPakeStart {
+    username: Vec<u8>,
+    a: Zeroizing<Vec<u8>>,
+}
+
+impl<D: Digest> PakeClient for SrpClient<'_, D> {
+    type Started = SrpPakeStart;
+    type Confirming = SrpClientVerifier<D>;
+    type Key = SessionKey<D>;
+    type Error = SrpAuthError;
+
+    fn start(
+        &self,
+        username: &[u8],
+        ephemeral: &[u8],
+    ) -> Result<(Vec<u8>, SrpPakeStart), SrpAuthError> {
+        let hello = ClientHello {
+            username: username.to_vec(),
+            a_pub: self.compute_public_ephemeral(ephemeral)?,
+        };
+        let state = SrpPakeStart {
+            username: hello.username.clone(),
+            a: Zeroizing::new(ephemeral.to_vec()),
+        };
+        Ok((hello.encode()?, state))
+    }
+
+    fn process_challenge(
+        &self,
+        state: SrpPakeStart,
+        password: &[u8],
+        challenge: &[u8],
+    ) -> Result<(Vec<u8>, SrpClientVerifier<D>), SrpAuthError> {
+        let challenge = ServerChallenge::decode(challenge)?;
+        let verifier = self.process_reply(
+            &state.a,
+            &state.username,
+            password,
+            &challenge.salt,
+            &challenge.b_pub,
+        )?;
+        let proof = ClientProof {
+            m1: verifier.proof().to_vec(),
+        };
+        Ok((proof.encode()?, verifier))
+    }
+
+    fn finish(
+        &self,
+        state: SrpClientVerifier<D>,
+        server_proof: &[u8],
+    ) -> Result<SessionKey<D>, SrpAuthError> {
+        let server_proof = ServerProof::decode(server_proof)?;
+        state.verify_server(&server_proof.m2)
+    }
+}
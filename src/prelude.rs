@@ -0,0 +1,8 @@
+// The names nearly every handshake needs:
+// `use apple_srp_client::prelude::*;`.
+#[cfg(feature = "std")]
+pub use crate::G_2048;
+pub use crate::{
+    BigUint, CompatProfile, Credentials, Digest, Output, PakeClient, SessionKey, SrpAuthError,
+    SrpClient, SrpClientVerifier, SrpGroup, SrpOptions,
+};
@@ -0,0 +1,84 @@
+#[cfg(feature = "dyn-digest")]
+use crate::DigestAlgorithm;
+use crate::SrpOptions;
+#[cfg(feature = "std")]
+use crate::{G_2048, SrpGroup};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CompatProfile {
+    #[default]
+    Apple,
+    Rfc5054,
+    PySrp,
+    NodeSrp,
+    Thinbus,
+    OpenSsl,
+}
+
+impl CompatProfile {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().replace(['-', '_'], "").as_str() {
+            "apple" => Some(Self::Apple),
+            "rfc5054" => Some(Self::Rfc5054),
+            "pysrp" => Some(Self::PySrp),
+            "node" | "nodesrp" => Some(Self::NodeSrp),
+            "thinbus" => Some(Self::Thinbus),
+            "openssl" => Some(Self::OpenSsl),
+            _ => None,
+        }
+    }
+
+    pub fn options(self) -> SrpOptions {
+        match self {
+            CompatProfile::Apple => SrpOptions::default(),
+            CompatProfile::Rfc5054 | CompatProfile::OpenSsl => SrpOptions {
+                pad_u: true,
+                username_in_x: true,
+                canonical_encoding: true,
+                ..SrpOptions::default()
+            },
+            CompatProfile::PySrp => SrpOptions {
+                pad_g: false,
+                username_in_x: true,
+                ..SrpOptions::default()
+            },
+            CompatProfile::NodeSrp => SrpOptions {
+                pad_g: false,
+                pad_key: true,
+                preserve_b_encoding: true,
+                username_in_x: true,
+                // node-srp prefixes B with a sign byte.
+                canonical_encoding: false,
+                ..SrpOptions::default()
+            },
+            CompatProfile::Thinbus => SrpOptions {
+                hex_hashing: true,
+                username_in_x: true,
+                ..SrpOptions::default()
+            },
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub fn group(self) -> &'static SrpGroup {
+        &G_2048
+    }
+
+    #[cfg(feature = "dyn-digest")]
+    pub fn digest(self) -> DigestAlgorithm {
+        match self {
+            CompatProfile::Apple | CompatProfile::NodeSrp | CompatProfile::Thinbus => {
+                DigestAlgorithm::Sha256
+            }
+            CompatProfile::Rfc5054 | CompatProfile::PySrp | CompatProfile::OpenSsl => {
+                DigestAlgorithm::Sha1
+            }
+        }
+    }
+}
+
+impl From<CompatProfile> for SrpOptions {
+    fn from(profile: CompatProfile) -> Self {
+        profile.options()
+    }
+}
@@ -0,0 +1,108 @@
+use alloc::{vec, vec::Vec};
+use core::ops::Deref;
+
+use num_bigint::BigUint;
+use subtle::{ConditionallySelectable, ConstantTimeEq};
+use zeroize::Zeroizing;
+
+#[cfg(all(feature = "mlock", unix))]
+use crate::LockedBytes;
+
+// Overwrites every limb of `value` in place. num-bigint has no zeroize
+// support and this cannot reach the copies its arithmetic made along the
+// way, so it narrows the window rather than closing it.
+pub(crate) fn wipe(value: &mut BigUint) {
+    let bits = value.bits();
+    if bits > 0 {
+        *value |= &((BigUint::from(1u8) << bits) - 1u8);
+    }
+}
+
+// A BigUint wiped on drop, for a, x, S and the values derived from them.
+pub(crate) struct SecretUint(pub(crate) BigUint);
+
+impl Deref for SecretUint {
+    type Target = BigUint;
+
+    fn deref(&self) -> &BigUint {
+        &self.0
+    }
+}
+
+impl Drop for SecretUint {
+    fn drop(&mut self) {
+        wipe(&mut self.0);
+    }
+}
+
+// Key material held by `SessionKey` and `Credentials`: a zeroizing heap
+// buffer until `lock` moves it into mlock()ed pages, and then only there.
+pub(crate) enum SecretBytes {
+    Heap(Zeroizing<Vec<u8>>),
+    #[cfg(all(feature = "mlock", unix))]
+    Locked(LockedBytes),
+}
+
+impl SecretBytes {
+    // The heap buffer is wiped when it is replaced, so after a successful
+    // call no ordinary heap copy remains. On error nothing changes.
+    #[cfg(all(feature = "mlock", unix))]
+    pub(crate) fn lock(&mut self) -> std::io::Result<()> {
+        if let Self::Heap(bytes) = self {
+            *self = Self::Locked(LockedBytes::new(bytes)?);
+        }
+        Ok(())
+    }
+
+    #[cfg(all(feature = "mlock", unix))]
+    pub(crate) fn is_locked(&self) -> bool {
+        matches!(self, Self::Locked(_))
+    }
+}
+
+impl From<Zeroizing<Vec<u8>>> for SecretBytes {
+    fn from(bytes: Zeroizing<Vec<u8>>) -> Self {
+        Self::Heap(bytes)
+    }
+}
+
+impl Deref for SecretBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Heap(bytes) => bytes,
+            #[cfg(all(feature = "mlock", unix))]
+            Self::Locked(bytes) => bytes.as_bytes(),
+        }
+    }
+}
+
+// Big-endian `value` in exactly `len` bytes, written limb by limb.
+// `to_bytes_be` trims leading zeros, so its length and the copies it makes
+// depend on the secret; this only depends on `len`. The value must fit.
+pub(crate) fn to_fixed_bytes(value: &BigUint, len: usize) -> Zeroizing<Vec<u8>> {
+    debug_assert!(value.bits().div_ceil(8) <= len as u64);
+    let mut out = Zeroizing::new(vec![0; len]);
+    for (i, limb) in value.iter_u64_digits().enumerate() {
+        for (j, byte) in limb.to_le_bytes().into_iter().enumerate() {
+            if let Some(index) = len.checked_sub(i * 8 + j + 1) {
+                out[index] = byte;
+            }
+        }
+    }
+    out
+}
+
+// The unpadded encoding the protocol hashes, taken from a fixed-length one.
+// The zero count is accumulated over every byte instead of stopping at the
+// first non-zero one.
+pub(crate) fn trim_leading_zeros(bytes: &[u8]) -> &[u8] {
+    let mut leading = 0u64;
+    let mut in_prefix = 1u8.into();
+    for byte in bytes {
+        in_prefix &= byte.ct_eq(&0);
+        leading += u64::conditional_select(&0, &1, in_prefix);
+    }
+    &bytes[leading as usize..]
+}
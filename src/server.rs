@@ -0,0 +1,151 @@
+use alloc::vec::Vec;
+
+use digest::{Digest, Output};
+use num_bigint::BigUint;
+
+use crate::{
+    SessionKey, SrpAuthError, SrpClient, SrpClientVerifier, SrpGroup, SrpOptions, constant_time_eq,
+    secret::{SecretUint, to_fixed_bytes},
+    validate,
+};
+
+// The verifier holder's side of the handshake. It reaches the same premaster
+// secret as `SrpClient` from v and b, then derives K, M1 and M2 exactly as a
+// client with the same options would, so any profile the client speaks can
+// be answered.
+pub struct SrpServer<'a, D: Digest> {
+    client: SrpClient<'a, D>,
+}
+
+// M1 and M2 for one handshake. M2 proves knowledge of v, so it is only
+// handed out by `verify_client` once the client's M1 has checked out.
+pub struct SrpServerVerifier<D: Digest> {
+    inner: SrpClientVerifier<D>,
+}
+
+impl<D: Digest> Clone for SrpServer<'_, D> {
+    fn clone(&self) -> Self {
+        Self {
+            client: self.client.clone(),
+        }
+    }
+}
+
+impl<'a, D: Digest> SrpServer<'a, D> {
+    pub fn new(params: &'a SrpGroup) -> Self {
+        Self::with_options(params, SrpOptions::default())
+    }
+
+    pub fn with_options(params: &'a SrpGroup, options: SrpOptions) -> Self {
+        Self {
+            client: SrpClient::with_options(params, options),
+        }
+    }
+
+    // The server half of `SrpClient::with_channel_binding`.
+    pub fn with_channel_binding<'b>(self, binding: &'b [u8]) -> SrpServer<'b, D>
+    where
+        'a: 'b,
+    {
+        SrpServer {
+            client: self.client.with_channel_binding(binding),
+        }
+    }
+
+    // B = k*v + g^b mod N. `b` must pass the same checks as the client's `a`
+    // and be kept secret until `process_hello` for this handshake.
+    pub fn compute_public_ephemeral(
+        &self,
+        b: &[u8],
+        verifier: &[u8],
+    ) -> Result<Vec<u8>, SrpAuthError> {
+        let b = SecretUint(self.private_ephemeral(b)?);
+        let v = SecretUint(self.verifier(verifier)?);
+        let b_pub = self.b_pub(&b, &v);
+        let b_pub = self.client.output_bytes(&b_pub);
+        Ok(self.client.options.wire_endianness.encode(b_pub))
+    }
+
+    fn private_ephemeral(&self, b: &[u8]) -> Result<BigUint, SrpAuthError> {
+        validate::ephemeral(b, &self.client.params).map_err(|_| SrpAuthError::IllegalParameter("b"))
+    }
+
+    fn verifier(&self, verifier: &[u8]) -> Result<BigUint, SrpAuthError> {
+        let v = BigUint::from_bytes_be(&self.client.options.wire_endianness.decode(verifier));
+        if !validate::group_member(&v, &self.client.params) {
+            return Err(SrpAuthError::IllegalParameter("verifier"));
+        }
+        Ok(v)
+    }
+
+    fn b_pub(&self, b: &BigUint, v: &BigUint) -> BigUint {
+        let n = &self.client.params.n;
+        let kv = SecretUint((self.client.compute_multiplier() * v) % n);
+        (&*kv + self.client.params.g.modpow(b, n)) % n
+    }
+
+    // Takes the client's A together with the `b`, salt and verifier the
+    // matching B was computed from, and derives S = (A * v^u)^b. A is checked
+    // as the client checks B, and S of 0 or 1 is refused.
+    pub fn process_hello(
+        &self,
+        b: &[u8],
+        username: &[u8],
+        salt: &[u8],
+        verifier: &[u8],
+        a_pub: &[u8],
+    ) -> Result<SrpServerVerifier<D>, SrpAuthError> {
+        let options = &self.client.options;
+        let params = &*self.client.params;
+        let b = SecretUint(self.private_ephemeral(b)?);
+        let v = SecretUint(self.verifier(verifier)?);
+        let salt = &*options.wire_endianness.decode(salt);
+        validate::salt(salt)?;
+        let a_pub = &*options.wire_endianness.decode(a_pub);
+        let a_pub = validate::a_pub(a_pub, params, options.canonical_encoding)?;
+        let b_pub = self.b_pub(&b, &v);
+        validate::reflection(&a_pub, &b_pub, params)?;
+
+        // The client hashes B as it arrived when `preserve_b_encoding` is
+        // set, which is the form `compute_public_ephemeral` emits.
+        let a_bytes = a_pub.to_bytes_be();
+        let b_bytes = if options.preserve_b_encoding {
+            self.client.output_bytes(&b_pub)
+        } else {
+            b_pub.to_bytes_be()
+        };
+        let u = self.client.compute_scrambler(&a_bytes, &b_bytes)?;
+
+        let n = &params.n;
+        let base = SecretUint((a_pub % n) * v.modpow(&u, n) % n);
+        let premaster = SecretUint(base.modpow(&b, n));
+        if *premaster <= BigUint::from(1u8) {
+            return Err(SrpAuthError::IllegalParameter("premaster_secret"));
+        }
+        let premaster = to_fixed_bytes(&premaster, params.byte_len());
+        let (m1, m2, key) = self
+            .client
+            .confirm(username, salt, &a_bytes, &b_bytes, &premaster);
+
+        Ok(SrpServerVerifier {
+            inner: SrpClientVerifier {
+                m1,
+                m2,
+                key,
+                #[cfg(feature = "unsafe-debug")]
+                intermediates: None,
+            },
+        })
+    }
+}
+
+impl<D: Digest> SrpServerVerifier<D> {
+    // Returns M2 for the client alongside the session key.
+    pub fn verify_client(self, m1: &[u8]) -> Result<(Output<D>, SessionKey<D>), SrpAuthError> {
+        if !constant_time_eq(&self.inner.m1, m1) {
+            return Err(SrpAuthError::BadRecordMac("client"));
+        }
+        let m2 = self.inner.m2.clone();
+        Ok((m2, self.inner.into_session_key()))
+    }
+}
@@ -0,0 +1,193 @@
+use alloc::vec::Vec;
+
+use aes_gcm::{
+    Aes256Gcm, KeyInit,
+    aead::{Aead, Nonce, Payload},
+};
+use digest::{Digest, core_api::BlockSizeUser};
+
+use crate::{SESSION_IV_LEN, SessionKey, SessionKeys, SrpAuthError};
+
+pub const SESSION_SEQUENCE_LEN: usize = 8;
+pub const SESSION_TAG_LEN: usize = 16;
+pub const REPLAY_WINDOW_LEN: u64 = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CipherRole {
+    Client,
+    Server,
+}
+
+struct Direction {
+    cipher: Aes256Gcm,
+    iv: [u8; SESSION_IV_LEN],
+}
+
+impl Direction {
+    fn new(key: &[u8], iv: &[u8]) -> Self {
+        let mut fixed = [0; SESSION_IV_LEN];
+        fixed.copy_from_slice(iv);
+        Self {
+            cipher: Aes256Gcm::new_from_slice(key).expect("session keys are 32 bytes"),
+            iv: fixed,
+        }
+    }
+
+    // The TLS 1.3 construction: the sequence number, left-padded to the IV
+    // length, XORed into the per-direction IV.
+    fn nonce(&self, sequence: u64) -> Nonce<Aes256Gcm> {
+        let mut nonce = self.iv;
+        for (byte, seq) in nonce[SESSION_IV_LEN - SESSION_SEQUENCE_LEN..]
+            .iter_mut()
+            .zip(sequence.to_be_bytes())
+        {
+            *byte ^= seq;
+        }
+        nonce.into()
+    }
+}
+
+// The sliding window of DTLS and IPsec: the highest sequence number seen and
+// a bitmap of the REPLAY_WINDOW_LEN numbers below it. Anything older than the
+// window or already in it is a replay.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReplayWindow {
+    highest: Option<u64>,
+    seen: u64,
+}
+
+impl ReplayWindow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn highest(&self) -> Option<u64> {
+        self.highest
+    }
+
+    pub fn check(&self, sequence: u64) -> bool {
+        match self.highest {
+            None => true,
+            Some(highest) if sequence > highest => true,
+            Some(highest) => {
+                let age = highest - sequence;
+                age < REPLAY_WINDOW_LEN && self.seen & (1 << age) == 0
+            }
+        }
+    }
+
+    // Records `sequence` as received. Callers check first and only accept
+    // records that authenticated.
+    pub fn accept(&mut self, sequence: u64) {
+        match self.highest {
+            Some(highest) if sequence <= highest => {
+                let age = highest - sequence;
+                if age < REPLAY_WINDOW_LEN {
+                    self.seen |= 1 << age;
+                }
+            }
+            highest => {
+                let shift = highest.map_or(REPLAY_WINDOW_LEN, |highest| sequence - highest);
+                self.seen = if shift < REPLAY_WINDOW_LEN {
+                    self.seen << shift | 1
+                } else {
+                    1
+                };
+                self.highest = Some(sequence);
+            }
+        }
+    }
+}
+
+// AES-256-GCM records keyed from `SessionKeys`, one key and IV per direction.
+// Every record is an 8-byte big-endian sequence number, the ciphertext and a
+// 16-byte tag. The sender counts up from zero and refuses to wrap, so a nonce
+// is never reused; the receiver runs a `ReplayWindow`, so records may arrive
+// late or not at all but never twice.
+pub struct SessionCipher {
+    seal: Direction,
+    open: Direction,
+    next_seal: u64,
+    window: ReplayWindow,
+}
+
+impl SessionCipher {
+    pub fn new<D: Digest + BlockSizeUser + Clone>(keys: &SessionKeys<D>, role: CipherRole) -> Self {
+        let client = Direction::new(keys.client_write_key(), keys.client_write_iv());
+        let server = Direction::new(keys.server_write_key(), keys.server_write_iv());
+        let (seal, open) = match role {
+            CipherRole::Client => (client, server),
+            CipherRole::Server => (server, client),
+        };
+        Self {
+            seal,
+            open,
+            next_seal: 0,
+            window: ReplayWindow::new(),
+        }
+    }
+
+    pub fn from_session_key<D: Digest + BlockSizeUser + Clone>(
+        key: &SessionKey<D>,
+        context: &[u8],
+        role: CipherRole,
+    ) -> Self {
+        Self::new(&SessionKeys::derive(key, context), role)
+    }
+
+    // The sequence number the next sealed record will carry.
+    pub fn next_sequence(&self) -> u64 {
+        self.next_seal
+    }
+
+    pub fn replay_window(&self) -> &ReplayWindow {
+        &self.window
+    }
+
+    pub fn seal(&mut self, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, SrpAuthError> {
+        let sequence = self.next_seal;
+        self.next_seal = sequence
+            .checked_add(1)
+            .ok_or(SrpAuthError::IllegalParameter("sequence"))?;
+        let ciphertext = self
+            .seal
+            .cipher
+            .encrypt(
+                &self.seal.nonce(sequence),
+                Payload {
+                    msg: plaintext,
+                    aad,
+                },
+            )
+            .map_err(|_| SrpAuthError::IllegalParameter("plaintext"))?;
+
+        let mut record = Vec::with_capacity(SESSION_SEQUENCE_LEN + ciphertext.len());
+        record.extend_from_slice(&sequence.to_be_bytes());
+        record.extend_from_slice(&ciphertext);
+        Ok(record)
+    }
+
+    pub fn open(&mut self, aad: &[u8], record: &[u8]) -> Result<Vec<u8>, SrpAuthError> {
+        if record.len() < SESSION_SEQUENCE_LEN + SESSION_TAG_LEN {
+            return Err(SrpAuthError::DecodeError("record"));
+        }
+        let (sequence, ciphertext) = record.split_at(SESSION_SEQUENCE_LEN);
+        let sequence = u64::from_be_bytes(sequence.try_into().expect("split at 8 bytes"));
+        if !self.window.check(sequence) {
+            return Err(SrpAuthError::IllegalParameter("sequence"));
+        }
+        let plaintext = self
+            .open
+            .cipher
+            .decrypt(
+                &self.open.nonce(sequence),
+                Payload {
+                    msg: ciphertext,
+                    aad,
+                },
+            )
+            .map_err(|_| SrpAuthError::BadRecordMac("record"))?;
+        self.window.accept(sequence);
+        Ok(plaintext)
+    }
+}
@@ -0,0 +1,156 @@
+use alloc::{borrow::ToOwned, string::String};
+use core::fmt;
+
+use rand_core::CryptoRngCore;
+
+use crate::SrpAuthError;
+
+pub const SETUP_FLAG_NFC: u8 = 0x1;
+pub const SETUP_FLAG_IP: u8 = 0x2;
+pub const SETUP_FLAG_BLE: u8 = 0x4;
+
+const SETUP_URI_PREFIX: &str = "X-HM://";
+const BASE36: &[u8; 36] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const PAYLOAD_LEN: usize = 9;
+const SETUP_ID_LEN: usize = 4;
+
+const TRIVIAL_CODES: [u32; 12] = [
+    0, 11111111, 22222222, 33333333, 44444444, 55555555, 66666666, 77777777, 88888888, 99999999,
+    12345678, 87654321,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetupCode(u32);
+
+impl SetupCode {
+    pub fn new(code: u32) -> Result<Self, SrpAuthError> {
+        if code >= 100_000_000 || TRIVIAL_CODES.contains(&code) {
+            return Err(SrpAuthError::IllegalParameter("setup_code"));
+        }
+        Ok(Self(code))
+    }
+
+    pub fn generate<R: CryptoRngCore + ?Sized>(rng: &mut R) -> Self {
+        // 4.2e9 is the largest multiple of 1e8 below 2^32, so rejecting
+        // anything above it leaves every code equally likely.
+        loop {
+            let value = rng.next_u32();
+            if value >= 4_200_000_000 {
+                continue;
+            }
+            if let Ok(code) = Self::new(value % 100_000_000) {
+                return code;
+            }
+        }
+    }
+
+    // Accepts both "XXX-XX-XXX" and the bare eight digits.
+    pub fn parse(code: &str) -> Result<Self, SrpAuthError> {
+        const ERR: SrpAuthError = SrpAuthError::DecodeError("setup_code");
+
+        let bytes = code.as_bytes();
+        let digits = if bytes.len() == 10 && bytes[3] == b'-' && bytes[6] == b'-' {
+            code.replace('-', "")
+        } else {
+            code.to_owned()
+        };
+        if digits.len() != 8 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ERR);
+        }
+        Self::new(digits.parse().map_err(|_| ERR)?)
+    }
+
+    pub fn value(self) -> u32 {
+        self.0
+    }
+}
+
+impl fmt::Display for SetupCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let code = self.0;
+        write!(
+            f,
+            "{:03}-{:02}-{:03}",
+            code / 100_000,
+            code / 1_000 % 100,
+            code % 1_000
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetupPayload {
+    pub setup_code: SetupCode,
+    pub setup_id: [u8; SETUP_ID_LEN],
+    pub category: u8,
+    pub flags: u8,
+}
+
+impl SetupPayload {
+    pub fn generate_setup_id<R: CryptoRngCore + ?Sized>(rng: &mut R) -> [u8; SETUP_ID_LEN] {
+        let mut id = [0; SETUP_ID_LEN];
+        for byte in &mut id {
+            // 252 is the largest multiple of 36 that fits in a byte.
+            *byte = loop {
+                let mut value = [0];
+                rng.fill_bytes(&mut value);
+                if value[0] < 252 {
+                    break BASE36[(value[0] % 36) as usize];
+                }
+            };
+        }
+        id
+    }
+
+    // The 45-bit payload packs, from the top: a 3-bit version and 4 reserved
+    // bits (both zero), the 8-bit accessory category, 4 transport flags and
+    // the 27-bit setup code. It is written as 9 base-36 digits.
+    pub fn to_uri(&self) -> String {
+        let mut payload = u64::from(self.category) << 31
+            | u64::from(self.flags & 0xf) << 27
+            | u64::from(self.setup_code.value());
+
+        let mut digits = [b'0'; PAYLOAD_LEN];
+        for digit in digits.iter_mut().rev() {
+            *digit = BASE36[(payload % 36) as usize];
+            payload /= 36;
+        }
+
+        let mut uri = String::from(SETUP_URI_PREFIX);
+        uri.extend(digits.iter().map(|&b| b as char));
+        uri.extend(self.setup_id.iter().map(|&b| b as char));
+        uri
+    }
+
+    pub fn from_uri(uri: &str) -> Result<Self, SrpAuthError> {
+        const ERR: SrpAuthError = SrpAuthError::DecodeError("setup_payload");
+
+        let rest = uri.strip_prefix(SETUP_URI_PREFIX).ok_or(ERR)?.as_bytes();
+        if rest.len() != PAYLOAD_LEN + SETUP_ID_LEN {
+            return Err(ERR);
+        }
+        let (digits, setup_id) = rest.split_at(PAYLOAD_LEN);
+
+        let mut payload = 0u64;
+        for &digit in digits {
+            let value = BASE36
+                .iter()
+                .position(|&b| b == digit.to_ascii_uppercase())
+                .ok_or(ERR)?;
+            payload = payload * 36 + value as u64;
+        }
+        if payload >> 39 != 0 {
+            return Err(ERR);
+        }
+        if !setup_id.iter().all(|b| BASE36.contains(b)) {
+            return Err(ERR);
+        }
+
+        Ok(Self {
+            setup_code: SetupCode::new((payload & 0x7ff_ffff) as u32)?,
+            setup_id: setup_id.try_into().map_err(|_| ERR)?,
+            category: (payload >> 31) as u8,
+            flags: (payload >> 27 & 0xf) as u8,
+        })
+    }
+}
@@ -0,0 +1,124 @@
+use alloc::sync::Arc;
+#[cfg(feature = "ed25519")]
+use alloc::vec::Vec;
+#[cfg(feature = "ed25519")]
+use core::convert::Infallible;
+
+#[cfg(feature = "ed25519")]
+use ed25519_dalek::{Signer, SigningKey};
+#[cfg(feature = "ed25519")]
+use rand_core::CryptoRngCore;
+#[cfg(feature = "ed25519")]
+use zeroize::Zeroizing;
+
+#[cfg(feature = "ed25519")]
+use crate::{LongTermKeys, SrpAuthError};
+
+pub const ED25519_PUBLIC_KEY_LEN: usize = 32;
+pub const ED25519_SIGNATURE_LEN: usize = 64;
+
+// The controller's long-term Ed25519 identity as used by Pair-Setup M5 and
+// Pair-Verify M3. Only the public half and signatures ever leave the
+// provider, so the secret can stay in a Secure Enclave, TPM or PKCS#11 token.
+pub trait SigningKeyProvider {
+    type Error;
+
+    fn identifier(&self) -> &[u8];
+
+    fn public_key(&self) -> Result<[u8; ED25519_PUBLIC_KEY_LEN], Self::Error>;
+
+    fn sign(&self, message: &[u8]) -> Result<[u8; ED25519_SIGNATURE_LEN], Self::Error>;
+}
+
+impl<P: SigningKeyProvider + ?Sized> SigningKeyProvider for &P {
+    type Error = P::Error;
+
+    fn identifier(&self) -> &[u8] {
+        (**self).identifier()
+    }
+
+    fn public_key(&self) -> Result<[u8; ED25519_PUBLIC_KEY_LEN], P::Error> {
+        (**self).public_key()
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<[u8; ED25519_SIGNATURE_LEN], P::Error> {
+        (**self).sign(message)
+    }
+}
+
+impl<P: SigningKeyProvider + ?Sized> SigningKeyProvider for Arc<P> {
+    type Error = P::Error;
+
+    fn identifier(&self) -> &[u8] {
+        (**self).identifier()
+    }
+
+    fn public_key(&self) -> Result<[u8; ED25519_PUBLIC_KEY_LEN], P::Error> {
+        (**self).public_key()
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<[u8; ED25519_SIGNATURE_LEN], P::Error> {
+        (**self).sign(message)
+    }
+}
+
+// The default provider: the key lives in process memory and round-trips
+// through `LongTermKeys`, so any `PairingStore` can persist it.
+#[cfg(feature = "ed25519")]
+pub struct SoftwareSigningKey {
+    identifier: Vec<u8>,
+    key: SigningKey,
+}
+
+#[cfg(feature = "ed25519")]
+impl SoftwareSigningKey {
+    pub fn generate<R: CryptoRngCore + ?Sized>(identifier: &[u8], rng: &mut R) -> Self {
+        let mut seed = Zeroizing::new([0; 32]);
+        rng.fill_bytes(&mut *seed);
+        Self {
+            identifier: identifier.to_vec(),
+            key: SigningKey::from_bytes(&seed),
+        }
+    }
+
+    // Rejects keys whose secret is not a 32-byte seed or whose stored public
+    // key does not belong to it.
+    pub fn from_keys(keys: &LongTermKeys) -> Result<Self, SrpAuthError> {
+        const ERR: SrpAuthError = SrpAuthError::IllegalParameter("long-term key");
+
+        let seed: &[u8; 32] = keys.secret_key.as_slice().try_into().map_err(|_| ERR)?;
+        let key = SigningKey::from_bytes(seed);
+        if key.verifying_key().as_bytes() != keys.public_key.as_slice() {
+            return Err(ERR);
+        }
+        Ok(Self {
+            identifier: keys.identifier.clone(),
+            key,
+        })
+    }
+
+    pub fn to_keys(&self) -> LongTermKeys {
+        LongTermKeys {
+            identifier: self.identifier.clone(),
+            public_key: self.key.verifying_key().to_bytes().to_vec(),
+            secret_key: Zeroizing::new(self.key.to_bytes().to_vec()),
+        }
+    }
+}
+
+#[cfg(feature = "ed25519")]
+impl SigningKeyProvider for SoftwareSigningKey {
+    type Error = Infallible;
+
+    fn identifier(&self) -> &[u8] {
+        &self.identifier
+    }
+
+    fn public_key(&self) -> Result<[u8; ED25519_PUBLIC_KEY_LEN], Infallible> {
+        Ok(self.key.verifying_key().to_bytes())
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<[u8; ED25519_SIGNATURE_LEN], Infallible> {
+        Ok(self.key.sign(message).to_bytes())
+    }
+}
@@ -0,0 +1,264 @@
+use std::{fmt, fs, path::Path};
+
+use rusqlite::{Connection, OptionalExtension, Transaction, ffi, params};
+use serde_json::Value;
+use zeroize::Zeroizing;
+
+use crate::{
+    KeyringError, KeyringKey, LongTermKeys, Pairing, PairingStore,
+    keyring::{derive_key, new_kdf, open, seal},
+};
+
+const SECRET_KEY_AAD: &[u8] = b"srp pairing sqlite secret key v1";
+const KEY_CHECK_AAD: &[u8] = b"srp pairing sqlite key check v1";
+
+// Applied in order; `PRAGMA user_version` records how many have run.
+const MIGRATIONS: &[&str] = &[
+    "
+    CREATE TABLE long_term_keys (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        identifier BLOB NOT NULL,
+        public_key BLOB NOT NULL,
+        secret_key BLOB NOT NULL
+    );
+    CREATE TABLE pairings (
+        identifier BLOB PRIMARY KEY,
+        public_key BLOB NOT NULL,
+        permissions INTEGER NOT NULL
+    );
+",
+    "
+    CREATE TABLE keyring (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        kdf TEXT NOT NULL,
+        nonce BLOB NOT NULL,
+        key_check BLOB NOT NULL
+    );
+    ALTER TABLE long_term_keys ADD COLUMN nonce BLOB;
+",
+];
+
+#[derive(Debug)]
+pub enum SqliteStoreError {
+    Sqlite(rusqlite::Error),
+    Keyring(KeyringError),
+}
+
+impl fmt::Display for SqliteStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SqliteStoreError::Sqlite(err) => write!(f, "pairing database error: {err}"),
+            SqliteStoreError::Keyring(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for SqliteStoreError {}
+
+impl From<rusqlite::Error> for SqliteStoreError {
+    fn from(err: rusqlite::Error) -> Self {
+        SqliteStoreError::Sqlite(err)
+    }
+}
+
+impl From<KeyringError> for SqliteStoreError {
+    fn from(err: KeyringError) -> Self {
+        SqliteStoreError::Keyring(err)
+    }
+}
+
+// The long-term secret key is sealed with ChaCha20-Poly1305 under a key
+// derived as `FileKeyring` derives its own, with the KDF parameters kept in
+// the database. Pairings and the public half are stored in the clear. Rows
+// written before encryption was added are sealed on the next open.
+// `secure_delete` zeroes the pages they leave behind, but a copy of the file
+// or its journal taken before then still holds the plaintext key.
+pub struct SqlitePairingStore {
+    conn: Connection,
+    key: Zeroizing<[u8; 32]>,
+}
+
+impl SqlitePairingStore {
+    // Creates the file owner-only (0600 on unix) if it does not exist yet;
+    // SQLite gives its journal the same mode.
+    pub fn open(path: impl AsRef<Path>, key: KeyringKey) -> Result<Self, SqliteStoreError> {
+        let path = path.as_ref();
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(false);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        options.open(path).map_err(KeyringError::Io)?;
+        Self::from_connection(Connection::open(path)?, key)
+    }
+
+    pub fn from_connection(
+        mut conn: Connection,
+        key: KeyringKey,
+    ) -> Result<Self, SqliteStoreError> {
+        conn.pragma_update(None, "secure_delete", true)?;
+        let tx = conn.transaction()?;
+        let version: usize = tx.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        // A newer build has migrated this database; writing our own version
+        // back would make the next upgrade re-run migrations it already has.
+        if version > MIGRATIONS.len() {
+            return Err(SqliteStoreError::Sqlite(rusqlite::Error::SqliteFailure(
+                ffi::Error::new(ffi::SQLITE_CANTOPEN),
+                Some(format!(
+                    "schema version {version} is newer than the supported {}",
+                    MIGRATIONS.len()
+                )),
+            )));
+        }
+        if version < MIGRATIONS.len() {
+            for migration in &MIGRATIONS[version..] {
+                tx.execute_batch(migration)?;
+            }
+            tx.pragma_update(None, "user_version", MIGRATIONS.len())?;
+        }
+
+        // `key_check` seals an empty message, so a wrong passphrase fails here
+        // even before any secret key has been stored.
+        let keyring: Option<(String, Vec<u8>, Vec<u8>)> = tx
+            .query_row(
+                "SELECT kdf, nonce, key_check FROM keyring WHERE id = 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?;
+        let key = match keyring {
+            Some((kdf, nonce, key_check)) => {
+                let kdf =
+                    serde_json::from_str::<Value>(&kdf).map_err(|_| KeyringError::Malformed)?;
+                let key = derive_key(&kdf, key)?;
+                open(&key, KEY_CHECK_AAD, &nonce, &key_check)?;
+                key
+            }
+            None => {
+                let kdf = new_kdf(&key);
+                let key = derive_key(&kdf, key)?;
+                let (nonce, key_check) = seal(&key, KEY_CHECK_AAD, &[]);
+                tx.execute(
+                    "INSERT INTO keyring (id, kdf, nonce, key_check) VALUES (1, ?1, ?2, ?3)",
+                    params![kdf.to_string(), nonce, key_check],
+                )?;
+                key
+            }
+        };
+
+        // A secret key stored before encryption was added has no nonce.
+        let plaintext: Option<Zeroizing<Vec<u8>>> = tx
+            .query_row(
+                "SELECT secret_key FROM long_term_keys WHERE id = 1 AND nonce IS NULL",
+                [],
+                |row| Ok(Zeroizing::new(row.get(0)?)),
+            )
+            .optional()?;
+        if let Some(plaintext) = plaintext {
+            let (nonce, sealed) = seal(&key, SECRET_KEY_AAD, &plaintext);
+            tx.execute(
+                "UPDATE long_term_keys SET secret_key = ?1, nonce = ?2 WHERE id = 1",
+                params![sealed, nonce],
+            )?;
+        }
+        tx.commit()?;
+        Ok(Self { conn, key })
+    }
+
+    pub fn save_pairings(&mut self, pairings: &[Pairing]) -> rusqlite::Result<()> {
+        let tx = self.conn.transaction()?;
+        for pairing in pairings {
+            upsert_pairing(&tx, pairing)?;
+        }
+        tx.commit()
+    }
+}
+
+fn upsert_pairing(tx: &Transaction<'_>, pairing: &Pairing) -> rusqlite::Result<()> {
+    tx.execute(
+        "INSERT INTO pairings (identifier, public_key, permissions) VALUES (?1, ?2, ?3)
+         ON CONFLICT (identifier) DO UPDATE
+         SET public_key = excluded.public_key, permissions = excluded.permissions",
+        params![pairing.identifier, pairing.public_key, pairing.permissions],
+    )?;
+    Ok(())
+}
+
+fn pairing_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Pairing> {
+    Ok(Pairing {
+        identifier: row.get(0)?,
+        public_key: row.get(1)?,
+        permissions: row.get(2)?,
+    })
+}
+
+impl PairingStore for SqlitePairingStore {
+    type Error = SqliteStoreError;
+
+    fn load_keys(&self) -> Result<Option<LongTermKeys>, SqliteStoreError> {
+        self.conn
+            .query_row(
+                "SELECT identifier, public_key, secret_key, nonce FROM long_term_keys WHERE id = 1",
+                [],
+                |row| {
+                    Ok((
+                        row.get::<_, Vec<u8>>(0)?,
+                        row.get::<_, Vec<u8>>(1)?,
+                        row.get::<_, Vec<u8>>(2)?,
+                        row.get::<_, Vec<u8>>(3)?,
+                    ))
+                },
+            )
+            .optional()?
+            .map(|(identifier, public_key, sealed, nonce)| {
+                Ok(LongTermKeys {
+                    identifier,
+                    public_key,
+                    secret_key: open(&self.key, SECRET_KEY_AAD, &nonce, &sealed)?,
+                })
+            })
+            .transpose()
+    }
+
+    fn save_keys(&mut self, keys: &LongTermKeys) -> Result<(), SqliteStoreError> {
+        let (nonce, sealed) = seal(&self.key, SECRET_KEY_AAD, &keys.secret_key);
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT OR REPLACE INTO long_term_keys (id, identifier, public_key, secret_key, nonce)
+             VALUES (1, ?1, ?2, ?3, ?4)",
+            params![keys.identifier, keys.public_key, sealed, nonce],
+        )?;
+        Ok(tx.commit()?)
+    }
+
+    fn load_pairing(&self, identifier: &[u8]) -> Result<Option<Pairing>, SqliteStoreError> {
+        let pairing = self
+            .conn
+            .query_row(
+                "SELECT identifier, public_key, permissions FROM pairings WHERE identifier = ?1",
+                [identifier],
+                pairing_from_row,
+            )
+            .optional()?;
+        Ok(pairing)
+    }
+
+    fn save_pairing(&mut self, pairing: &Pairing) -> Result<(), SqliteStoreError> {
+        Ok(self.save_pairings(std::slice::from_ref(pairing))?)
+    }
+
+    fn remove_pairing(&mut self, identifier: &[u8]) -> Result<(), SqliteStoreError> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM pairings WHERE identifier = ?1", [identifier])?;
+        Ok(tx.commit()?)
+    }
+
+    fn list_pairings(&self) -> Result<Vec<Pairing>, SqliteStoreError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT identifier, public_key, permissions FROM pairings ORDER BY identifier",
+        )?;
+        let pairings = stmt
+            .query_map([], pairing_from_row)?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(pairings)
+    }
+}
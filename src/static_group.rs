@@ -0,0 +1,58 @@
+use digest::{Digest, OutputSizeUser, typenum::Unsigned};
+
+use crate::{G_2048, G_3072, SrpClient, SrpGroup, SrpOptions};
+
+// A group whose size is known at compile time, so `SrpClient::for_group` can
+// reject a digest too short for it before anything runs.
+pub trait StaticGroup {
+    const BITS: usize;
+
+    fn group() -> &'static SrpGroup;
+}
+
+pub struct Group2048;
+
+impl StaticGroup for Group2048 {
+    const BITS: usize = 2048;
+
+    fn group() -> &'static SrpGroup {
+        &G_2048
+    }
+}
+
+pub struct Group3072;
+
+impl StaticGroup for Group3072 {
+    const BITS: usize = 3072;
+
+    fn group() -> &'static SrpGroup {
+        &G_3072
+    }
+}
+
+// Twice the security strength SP 800-57 assigns to a modulus of
+// `group_bits`, so the digest is never the weaker half of the handshake.
+pub const fn min_digest_bits(group_bits: usize) -> usize {
+    match group_bits {
+        0..=1024 => 160,
+        1025..=2048 => 224,
+        2049..=3072 => 256,
+        3073..=7680 => 384,
+        _ => 512,
+    }
+}
+
+impl<D: Digest> SrpClient<'static, D> {
+    // Fails to compile when D is too short for G, e.g. SHA-1 with the 2048-bit
+    // group. Legacy peers that need such a pairing go through `with_options`,
+    // which takes any runtime group and checks nothing.
+    pub fn for_group<G: StaticGroup>(options: SrpOptions) -> Self {
+        const {
+            assert!(
+                <D as OutputSizeUser>::OutputSize::USIZE * 8 >= min_digest_bits(G::BITS),
+                "digest output is too short for this group"
+            )
+        };
+        Self::with_options(G::group(), options)
+    }
+}
@@ -0,0 +1,138 @@
+use crate::{CompatProfile, DigestAlgorithm, DynSrpClient, DynSrpClientVerifier, SrpAuthError};
+
+// Exposed to Swift through the `swift` feature. The generated glue speaks
+// RustVec<UInt8>; `swift/AppleSrpClient.swift` wraps it into a Data-based API
+// whose methods throw `SwiftSrpError`.
+#[swift_bridge::bridge]
+mod ffi {
+    extern "Rust" {
+        type SwiftSrpClient;
+
+        fn srp_client_new(profile: &str, digest: &str) -> Result<SwiftSrpClient, SwiftSrpError>;
+
+        fn compute_verifier(
+            self: &SwiftSrpClient,
+            username: Vec<u8>,
+            password: Vec<u8>,
+            salt: Vec<u8>,
+        ) -> Vec<u8>;
+
+        fn compute_public_ephemeral(
+            self: &SwiftSrpClient,
+            a: Vec<u8>,
+        ) -> Result<Vec<u8>, SwiftSrpError>;
+
+        fn process_reply(
+            self: &SwiftSrpClient,
+            a: Vec<u8>,
+            username: Vec<u8>,
+            password: Vec<u8>,
+            salt: Vec<u8>,
+            b_pub: Vec<u8>,
+        ) -> Result<SwiftSrpVerifier, SwiftSrpError>;
+    }
+
+    extern "Rust" {
+        type SwiftSrpVerifier;
+
+        fn proof(self: &SwiftSrpVerifier) -> Vec<u8>;
+
+        fn verify_server(
+            self: &mut SwiftSrpVerifier,
+            m2: Vec<u8>,
+        ) -> Result<Vec<u8>, SwiftSrpError>;
+    }
+
+    extern "Rust" {
+        type SwiftSrpError;
+
+        fn message(self: &SwiftSrpError) -> String;
+    }
+}
+
+pub struct SwiftSrpClient {
+    inner: DynSrpClient<'static>,
+}
+
+pub struct SwiftSrpVerifier {
+    inner: Option<DynSrpClientVerifier>,
+}
+
+pub struct SwiftSrpError {
+    message: String,
+}
+
+impl From<SrpAuthError> for SwiftSrpError {
+    fn from(err: SrpAuthError) -> Self {
+        Self {
+            message: err.to_string(),
+        }
+    }
+}
+
+// An empty name selects the default profile or that profile's digest.
+fn srp_client_new(profile: &str, digest: &str) -> Result<SwiftSrpClient, SwiftSrpError> {
+    let profile = match profile {
+        "" => CompatProfile::default(),
+        name => CompatProfile::from_name(name).ok_or_else(|| SwiftSrpError {
+            message: format!("unknown profile '{name}'"),
+        })?,
+    };
+    let algorithm = match digest {
+        "" => profile.digest(),
+        name => DigestAlgorithm::from_name(name).ok_or_else(|| SwiftSrpError {
+            message: format!("unknown digest '{name}'"),
+        })?,
+    };
+    Ok(SwiftSrpClient {
+        inner: DynSrpClient::with_options(profile.group(), algorithm, profile.options()),
+    })
+}
+
+impl SwiftSrpClient {
+    fn compute_verifier(&self, username: Vec<u8>, password: Vec<u8>, salt: Vec<u8>) -> Vec<u8> {
+        self.inner.compute_verifier(&username, &password, &salt)
+    }
+
+    fn compute_public_ephemeral(&self, a: Vec<u8>) -> Result<Vec<u8>, SwiftSrpError> {
+        Ok(self.inner.compute_public_ephemeral(&a)?)
+    }
+
+    fn process_reply(
+        &self,
+        a: Vec<u8>,
+        username: Vec<u8>,
+        password: Vec<u8>,
+        salt: Vec<u8>,
+        b_pub: Vec<u8>,
+    ) -> Result<SwiftSrpVerifier, SwiftSrpError> {
+        let verifier = self
+            .inner
+            .process_reply(&a, &username, &password, &salt, &b_pub)?;
+        Ok(SwiftSrpVerifier {
+            inner: Some(verifier),
+        })
+    }
+}
+
+impl SwiftSrpVerifier {
+    fn proof(&self) -> Vec<u8> {
+        self.inner
+            .as_ref()
+            .map(|verifier| verifier.proof().to_vec())
+            .unwrap_or_default()
+    }
+
+    fn verify_server(&mut self, m2: Vec<u8>) -> Result<Vec<u8>, SwiftSrpError> {
+        let verifier = self.inner.take().ok_or_else(|| SwiftSrpError {
+            message: String::from("verifier already consumed"),
+        })?;
+        Ok(verifier.verify_server(&m2)?.as_bytes().to_vec())
+    }
+}
+
+impl SwiftSrpError {
+    fn message(&self) -> String {
+        self.message.clone()
+    }
+}
@@ -0,0 +1,55 @@
+use alloc::vec::Vec;
+
+use crate::SrpAuthError;
+
+pub const TLV_METHOD: u8 = 0x00;
+pub const TLV_IDENTIFIER: u8 = 0x01;
+pub const TLV_SALT: u8 = 0x02;
+pub const TLV_PUBLIC_KEY: u8 = 0x03;
+pub const TLV_PROOF: u8 = 0x04;
+pub const TLV_ENCRYPTED_DATA: u8 = 0x05;
+pub const TLV_STATE: u8 = 0x06;
+pub const TLV_ERROR: u8 = 0x07;
+pub const TLV_RETRY_DELAY: u8 = 0x08;
+pub const TLV_SIGNATURE: u8 = 0x0a;
+pub const TLV_PERMISSIONS: u8 = 0x0b;
+pub const TLV_SEPARATOR: u8 = 0xff;
+
+// Values longer than 255 bytes are split into consecutive items of the same
+// type, and decoding joins them back together. Two distinct values of the same
+// type therefore need a separator item between them.
+pub fn encode_tlv8(items: &[(u8, &[u8])]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for &(ty, value) in items {
+        if value.is_empty() {
+            out.extend_from_slice(&[ty, 0]);
+        }
+        for chunk in value.chunks(255) {
+            out.extend_from_slice(&[ty, chunk.len() as u8]);
+            out.extend_from_slice(chunk);
+        }
+    }
+    out
+}
+
+pub fn decode_tlv8(mut buf: &[u8]) -> Result<Vec<(u8, Vec<u8>)>, SrpAuthError> {
+    let mut items: Vec<(u8, Vec<u8>)> = Vec::new();
+    let mut continues = false;
+    while let Some((&[ty, len], rest)) = buf.split_first_chunk::<2>() {
+        let len = len as usize;
+        if rest.len() < len {
+            return Err(SrpAuthError::DecodeError("tlv8"));
+        }
+        let (value, rest) = rest.split_at(len);
+        match items.last_mut() {
+            Some((last, joined)) if continues && *last == ty => joined.extend_from_slice(value),
+            _ => items.push((ty, value.to_vec())),
+        }
+        continues = len == 255;
+        buf = rest;
+    }
+    if !buf.is_empty() {
+        return Err(SrpAuthError::DecodeError("tlv8"));
+    }
+    Ok(items)
+}
@@ -0,0 +1,249 @@
+use alloc::{string::String, vec::Vec};
+use core::cell::RefCell;
+
+#[cfg(feature = "json")]
+use serde_json::{Value, json};
+
+#[cfg(feature = "json")]
+use crate::{
+    ClientHello, ClientProof, Endianness, SaltOrder, ServerChallenge, ServerProof, SrpGroup,
+    SrpOptions,
+};
+use crate::{PakeClient, SrpAuthError, constant_time_eq, decode_hex, encode_hex};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageDirection {
+    ClientToServer,
+    ServerToClient,
+}
+
+// The public messages of one handshake, in order. Fixtures are plain text with
+// one message per line: "> hex" for client to server, "< hex" for server to
+// client; blank lines and lines starting with '#' are ignored.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HandshakeTranscript {
+    pub messages: Vec<(MessageDirection, Vec<u8>)>,
+}
+
+impl HandshakeTranscript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, direction: MessageDirection, message: &[u8]) {
+        self.messages.push((direction, message.to_vec()));
+    }
+
+    pub fn to_fixture(&self) -> String {
+        let mut out = String::new();
+        for (direction, message) in &self.messages {
+            out.push_str(match direction {
+                MessageDirection::ClientToServer => "> ",
+                MessageDirection::ServerToClient => "< ",
+            });
+            out.push_str(&encode_hex(message));
+            out.push('\n');
+        }
+        out
+    }
+
+    pub fn from_fixture(fixture: &str) -> Result<Self, SrpAuthError> {
+        let mut transcript = Self::new();
+        for line in fixture.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (direction, hex) = match line.split_at_checked(2) {
+                Some(("> ", hex)) => (MessageDirection::ClientToServer, hex),
+                Some(("< ", hex)) => (MessageDirection::ServerToClient, hex),
+                _ => return Err(SrpAuthError::DecodeError("transcript")),
+            };
+            transcript.record(direction, &decode_hex(hex)?);
+        }
+        Ok(transcript)
+    }
+
+    // Drives `client` through the recorded handshake: server messages are fed
+    // in from the transcript and every client message must match the recording
+    // byte for byte. The ephemeral and password must be the ones used when the
+    // transcript was captured.
+    pub fn replay<P>(
+        &self,
+        client: &P,
+        username: &[u8],
+        ephemeral: &[u8],
+        password: &[u8],
+    ) -> Result<P::Key, P::Error>
+    where
+        P: PakeClient,
+        P::Error: From<SrpAuthError>,
+    {
+        use MessageDirection::{ClientToServer, ServerToClient};
+
+        let [
+            (ClientToServer, hello),
+            (ServerToClient, challenge),
+            (ClientToServer, proof),
+            (ServerToClient, server_proof),
+        ] = &self.messages[..]
+        else {
+            return Err(SrpAuthError::DecodeError("transcript").into());
+        };
+
+        let (sent, started) = client.start(username, ephemeral)?;
+        if !constant_time_eq(&sent, hello) {
+            return Err(SrpAuthError::IllegalParameter("transcript").into());
+        }
+        let (sent, confirming) = client.process_challenge(started, password, challenge)?;
+        if !constant_time_eq(&sent, proof) {
+            return Err(SrpAuthError::IllegalParameter("transcript").into());
+        }
+        client.finish(confirming, server_proof)
+    }
+
+    // Pretty-printed JSON of the group, the options and digest label the
+    // handshake ran with, and every message both raw and split into its
+    // fields, so two implementations' dumps of the same handshake can be
+    // diffed line by line. Messages that do not decode as the one expected at
+    // their position keep only their raw bytes.
+    #[cfg(feature = "json")]
+    pub fn to_json(&self, params: &SrpGroup, options: &SrpOptions, digest: &str) -> String {
+        let config = json!({
+            "digest": digest,
+            "group": {
+                "bits": params.n.bits(),
+                "n": encode_hex(&params.n.to_bytes_be()),
+                "g": encode_hex(&params.g.to_bytes_be()),
+            },
+            "group_binding": options.group_binding.map(encode_hex),
+            "pad_g": options.pad_g,
+            "pad_u": options.pad_u,
+            "pad_key": options.pad_key,
+            "pad_output": options.pad_output,
+            "preserve_b_encoding": options.preserve_b_encoding,
+            "hex_hashing": options.hex_hashing,
+            "username_in_x": options.username_in_x,
+            "salt_order": match options.salt_order {
+                SaltOrder::SaltFirst => "salt_first",
+                SaltOrder::SaltLast => "salt_last",
+            },
+            "wire_endianness": match options.wire_endianness {
+                Endianness::Big => "big",
+                Endianness::Little => "little",
+            },
+            "canonical_encoding": options.canonical_encoding,
+            "domain_separation": options.domain_separation,
+        });
+        let messages = self
+            .messages
+            .iter()
+            .enumerate()
+            .map(|(i, (direction, message))| {
+                let mut entry = json!({
+                    "direction": match direction {
+                        MessageDirection::ClientToServer => "client_to_server",
+                        MessageDirection::ServerToClient => "server_to_client",
+                    },
+                    "raw": encode_hex(message),
+                });
+                if let (Value::Object(entry), Some(Value::Object(fields))) =
+                    (&mut entry, message_fields(i, message))
+                {
+                    entry.extend(fields);
+                }
+                entry
+            })
+            .collect::<Vec<_>>();
+        let transcript = json!({ "config": config, "messages": messages });
+        serde_json::to_string_pretty(&transcript).expect("JSON values always serialize")
+    }
+}
+
+#[cfg(feature = "json")]
+fn message_fields(position: usize, message: &[u8]) -> Option<Value> {
+    Some(match position {
+        0 => {
+            let hello = ClientHello::decode(message).ok()?;
+            json!({
+                "type": "client_hello",
+                "username": encode_hex(&hello.username),
+                "a_pub": encode_hex(&hello.a_pub),
+            })
+        }
+        1 => {
+            let challenge = ServerChallenge::decode(message).ok()?;
+            json!({
+                "type": "server_challenge",
+                "salt": encode_hex(&challenge.salt),
+                "b_pub": encode_hex(&challenge.b_pub),
+            })
+        }
+        2 => json!({
+            "type": "client_proof",
+            "m1": encode_hex(&ClientProof::decode(message).ok()?.m1),
+        }),
+        3 => json!({
+            "type": "server_proof",
+            "m2": encode_hex(&ServerProof::decode(message).ok()?.m2),
+        }),
+        _ => return None,
+    })
+}
+
+// Wraps a `PakeClient` and records every message it sends or is handed, so a
+// live handshake can be saved with `into_transcript().to_fixture()`.
+pub struct RecordingClient<P> {
+    inner: P,
+    transcript: RefCell<HandshakeTranscript>,
+}
+
+impl<P> RecordingClient<P> {
+    pub fn new(inner: P) -> Self {
+        Self {
+            inner,
+            transcript: RefCell::new(HandshakeTranscript::new()),
+        }
+    }
+
+    pub fn transcript(&self) -> HandshakeTranscript {
+        self.transcript.borrow().clone()
+    }
+
+    pub fn into_transcript(self) -> HandshakeTranscript {
+        self.transcript.into_inner()
+    }
+
+    fn record(&self, direction: MessageDirection, message: &[u8]) {
+        self.transcript.borrow_mut().record(direction, message);
+    }
+}
+
+impl<P: PakeClient> PakeClient for RecordingClient<P> {
+    type Started = P::Started;
+    type Confirming = P::Confirming;
+    type Key = P::Key;
+    type Error = P::Error;
+
+    fn start(&self, username: &[u8], ephemeral: &[u8]) -> Result<(Vec<u8>, P::Started), P::Error> {
+        let (hello, state) = self.inner.start(username, ephemeral)?;
+        self.record(MessageDirection::ClientToServer, &hello);
+        Ok((hello, state))
+    }
+
+    fn process_challenge(
+        &self,
+        state: P::Started,
+        password: &[u8],
+        challenge: &[u8],
+    ) -> Result<(Vec<u8>, P::Confirming), P::Error> {
+        self.record(MessageDirection::ServerToClient, challenge);
+        let (proof, state) = self.inner.process_challenge(state, password, challenge)?;
+        self.record(MessageDirection::ClientToServer, &proof);
+        Ok((proof, state))
+    }
+
+    fn finish(&self, state: P::Confirming, server_proof: &[u8]) -> Result<P::Key, P::Error> {
+        self.record(MessageDirection::ServerToClient, server_proof);
+        self.inner.finish(state, server_proof)
+    }
+}
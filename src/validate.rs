@@ -0,0 +1,94 @@
+use num_bigint::BigUint;
+
+use crate::{EPHEMERAL_LEN, SrpAuthError, SrpGroup, secret::wipe};
+
+// The checks `SrpClient::process_reply` applies, callable one at a time so a
+// gateway can screen handshake messages with exactly the same rules. Values
+// are big-endian; `canonical` selects the strict RFC 5054 encoding rules of
+// `SrpOptions::canonical_encoding`.
+
+// Empty, or all zero bytes: what an unset or zeroed database column looks
+// like, not something a working RNG produces.
+pub fn salt(salt: &[u8]) -> Result<(), SrpAuthError> {
+    if salt.iter().all(|&byte| byte == 0) {
+        return Err(SrpAuthError::IllegalParameter("salt"));
+    }
+    Ok(())
+}
+
+// The private ephemeral: at least EPHEMERAL_LEN bytes (leading zeros count,
+// as they do in half of all random buffers) and in [1, N).
+pub fn ephemeral(a: &[u8], params: &SrpGroup) -> Result<BigUint, SrpAuthError> {
+    let mut value = BigUint::from_bytes_be(a);
+    if a.len() < EPHEMERAL_LEN || value == BigUint::default() || value >= params.n {
+        wipe(&mut value);
+        return Err(SrpAuthError::IllegalParameter("a"));
+    }
+    Ok(value)
+}
+
+// Canonical encodings are non-empty, carry no leading zero byte and are no
+// longer than N.
+pub fn canonical_encoding(
+    value: &[u8],
+    params: &SrpGroup,
+    name: &'static str,
+) -> Result<(), SrpAuthError> {
+    if matches!(value.first(), None | Some(0)) || value.len() > params.byte_len() {
+        return Err(SrpAuthError::IllegalParameter(name));
+    }
+    Ok(())
+}
+
+// A public ephemeral must not be 0 mod N, and may never carry more
+// significant bytes than N. Leading zeros are tolerated (node-srp emits a sign
+// byte) unless `canonical`, which also requires the value to be below N.
+fn public_value(
+    value: &[u8],
+    params: &SrpGroup,
+    canonical: bool,
+    name: &'static str,
+) -> Result<BigUint, SrpAuthError> {
+    if canonical {
+        canonical_encoding(value, params, name)?;
+    }
+    if value.iter().skip_while(|&&byte| byte == 0).count() > params.byte_len() {
+        return Err(SrpAuthError::IllegalParameter(name));
+    }
+    let parsed = BigUint::from_bytes_be(value);
+    if !group_member(&(&parsed % &params.n), params) || (canonical && parsed >= params.n) {
+        return Err(SrpAuthError::IllegalParameter(name));
+    }
+    Ok(parsed)
+}
+
+pub fn a_pub(a_pub: &[u8], params: &SrpGroup, canonical: bool) -> Result<BigUint, SrpAuthError> {
+    public_value(a_pub, params, canonical, "a_pub")
+}
+
+pub fn b_pub(b_pub: &[u8], params: &SrpGroup, canonical: bool) -> Result<BigUint, SrpAuthError> {
+    public_value(b_pub, params, canonical, "b_pub")
+}
+
+// No honest server answers with the client's own A as B; seeing it means the
+// server is being used as a reflector. Compared mod N, so an added multiple of
+// N does not hide it.
+pub fn reflection(a_pub: &BigUint, b_pub: &BigUint, params: &SrpGroup) -> Result<(), SrpAuthError> {
+    if a_pub % &params.n == b_pub % &params.n {
+        return Err(SrpAuthError::IllegalParameter("b_pub"));
+    }
+    Ok(())
+}
+
+// Whether `value` lies in the multiplicative group, i.e. 0 < value < N.
+pub fn group_member(value: &BigUint, params: &SrpGroup) -> bool {
+    *value != BigUint::default() && *value < params.n
+}
+
+// u = 0 would remove the password from the premaster secret.
+pub fn u(u: &BigUint) -> Result<(), SrpAuthError> {
+    if *u == BigUint::default() {
+        return Err(SrpAuthError::IllegalParameter("u"));
+    }
+    Ok(())
+}
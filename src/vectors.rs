@@ -0,0 +1,110 @@
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+use digest::Digest;
+use num_bigint::BigUint;
+
+use crate::{SrpAuthError, SrpClient, SrpGroup};
+
+const EPHEMERAL: [u8; 32] = [0xc2; 32];
+const SALT: [u8; 16] = [0x5a; 16];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NegativeCase {
+    Challenge { salt: Vec<u8>, b_pub: Vec<u8> },
+    TruncatedServerProof { len: usize },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NegativeVector {
+    pub name: &'static str,
+    pub case: NegativeCase,
+}
+
+pub fn negative_vectors(params: &SrpGroup) -> Vec<NegativeVector> {
+    let n = &params.n;
+    let n_len = params.byte_len();
+    let challenge = |name, salt: &[u8], b_pub: Vec<u8>| NegativeVector {
+        name,
+        case: NegativeCase::Challenge {
+            salt: salt.to_vec(),
+            b_pub,
+        },
+    };
+    let truncated = |name, len| NegativeVector {
+        name,
+        case: NegativeCase::TruncatedServerProof { len },
+    };
+
+    vec![
+        challenge("b_empty", &SALT, Vec::new()),
+        challenge("b_zero", &SALT, vec![0]),
+        challenge("b_zero_padded", &SALT, vec![0; n_len]),
+        challenge("b_equals_n", &SALT, n.to_bytes_be()),
+        challenge("b_multiple_of_n", &SALT, (n * 2u32).to_bytes_be()),
+        challenge("b_huge", &SALT, vec![0xff; n_len * 2]),
+        challenge("salt_empty", &[], BigUint::from(2u32).to_bytes_be()),
+        challenge(
+            "salt_all_zero",
+            &[0u8; 16],
+            BigUint::from(2u32).to_bytes_be(),
+        ),
+        truncated("m2_empty", 0),
+        truncated("m2_one_byte", 1),
+        truncated("m2_missing_last_byte", usize::MAX),
+    ]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NegativeVectorFailure {
+    // The client accepted the named vector.
+    Accepted(&'static str),
+    // The honest handshake a vector builds on was refused, so the vector
+    // itself never ran.
+    Setup {
+        name: &'static str,
+        error: SrpAuthError,
+    },
+}
+
+impl fmt::Display for NegativeVectorFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NegativeVectorFailure::Accepted(name) => write!(f, "negative vector {name} accepted"),
+            NegativeVectorFailure::Setup { name, error } => {
+                write!(f, "negative vector {name} could not be set up: {error}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for NegativeVectorFailure {}
+
+// Stops at the first vector the client accepted or could not set up.
+pub fn run_negative_vectors<D: Digest>(client: &SrpClient<D>) -> Result<(), NegativeVectorFailure> {
+    let encode = |bytes: Vec<u8>| client.options.wire_endianness.encode(bytes);
+
+    for vector in negative_vectors(&client.params) {
+        let rejected = match vector.case {
+            NegativeCase::Challenge { salt, b_pub } => client
+                .process_reply(&EPHEMERAL, b"user", b"password", &salt, &encode(b_pub))
+                .is_err(),
+            NegativeCase::TruncatedServerProof { len } => {
+                let b_pub = encode(BigUint::from(2u32).to_bytes_be());
+                let verifier = client
+                    .process_reply(&EPHEMERAL, b"user", b"password", &SALT, &b_pub)
+                    .map_err(|error| NegativeVectorFailure::Setup {
+                        name: vector.name,
+                        error,
+                    })?;
+                let m2 = verifier.m2.to_vec();
+                let len = len.min(m2.len() - 1);
+                verifier.verify_server(&m2[..len]).is_err()
+            }
+        };
+        if !rejected {
+            return Err(NegativeVectorFailure::Accepted(vector.name));
+        }
+    }
+    Ok(())
+}
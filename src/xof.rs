@@ -0,0 +1,144 @@
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+
+use digest::ExtendableOutput;
+use num_bigint::BigUint;
+use zeroize::Zeroizing;
+
+use crate::{
+    SrpAuthError, SrpGroup, constant_time_eq, normalize_password,
+    secret::{SecretUint, to_fixed_bytes, trim_leading_zeros},
+    validate,
+};
+
+pub const XOF_HASH_LEN: usize = 64;
+
+fn xof<X: ExtendableOutput + Default>(parts: &[&[u8]], len: usize) -> Vec<u8> {
+    let mut hasher = X::default();
+    for part in parts {
+        hasher.update(part);
+    }
+    let mut out = vec![0; len];
+    hasher.finalize_xof_into(&mut out);
+    out
+}
+
+// SRP-6a over an extendable-output function such as SHAKE256 or BLAKE3. u,
+// k, x, M1 and M2 are `hash_len` bytes (XOF_HASH_LEN unless changed) and the
+// session key is `key_len` bytes, both chosen by the caller. The hash inputs
+// are RFC 5054's, with the username in x; there are no interop options.
+pub struct XofSrpClient<'a, X> {
+    params: &'a SrpGroup,
+    hash_len: usize,
+    key_len: usize,
+    x: PhantomData<X>,
+}
+
+pub struct XofSrpClientVerifier {
+    m1: Vec<u8>,
+    m2: Vec<u8>,
+    key: Zeroizing<Vec<u8>>,
+}
+
+impl<'a, X: ExtendableOutput + Default> XofSrpClient<'a, X> {
+    pub fn new(params: &'a SrpGroup, key_len: usize) -> Self {
+        Self {
+            params,
+            hash_len: XOF_HASH_LEN,
+            key_len,
+            x: PhantomData,
+        }
+    }
+
+    pub fn with_hash_len(self, hash_len: usize) -> Self {
+        Self { hash_len, ..self }
+    }
+
+    fn hash(&self, parts: &[&[u8]]) -> Vec<u8> {
+        xof::<X>(parts, self.hash_len)
+    }
+
+    fn compute_x(&self, username: &[u8], password: &[u8], salt: &[u8]) -> BigUint {
+        let password = normalize_password(password);
+        let identity_hash = Zeroizing::new(self.hash(&[username, b":", &password]));
+        BigUint::from_bytes_be(&self.hash(&[salt, &identity_hash]))
+    }
+
+    fn compute_k(&self) -> BigUint {
+        let n = self.params.n.to_bytes_be();
+        let g = self.params.pad_to_group(&self.params.g.to_bytes_be());
+        BigUint::from_bytes_be(&self.hash(&[&n, &g]))
+    }
+
+    pub fn compute_verifier(&self, username: &[u8], password: &[u8], salt: &[u8]) -> Vec<u8> {
+        let x = self.compute_x(username, password, salt);
+        self.params.g.modpow(&x, &self.params.n).to_bytes_be()
+    }
+
+    pub fn compute_public_ephemeral(&self, a: &[u8]) -> Result<Vec<u8>, SrpAuthError> {
+        let a = SecretUint(validate::ephemeral(a, self.params)?);
+        Ok(self.params.g.modpow(&a, &self.params.n).to_bytes_be())
+    }
+
+    pub fn process_reply(
+        &self,
+        a: &[u8],
+        username: &[u8],
+        password: &[u8],
+        salt: &[u8],
+        b_pub: &[u8],
+    ) -> Result<XofSrpClientVerifier, SrpAuthError> {
+        let params = self.params;
+        let n = &params.n;
+        let a = SecretUint(validate::ephemeral(a, params)?);
+        validate::salt(salt)?;
+        let b = validate::b_pub(b_pub, params, false)?;
+        if self.hash_len == 0 || self.key_len == 0 {
+            return Err(SrpAuthError::IllegalParameter("len"));
+        }
+
+        let a_pub = params.g.modpow(&a, n);
+        validate::reflection(&a_pub, &b, params)?;
+        let a_bytes = a_pub.to_bytes_be();
+        let b_bytes = b.to_bytes_be();
+        let u = BigUint::from_bytes_be(&self.hash(&[&a_bytes, &b_bytes]));
+        validate::u(&u)?;
+        let x = SecretUint(self.compute_x(username, password, salt));
+
+        let kv = SecretUint((self.compute_k() * params.g.modpow(&x, n)) % n);
+        let b = b % n;
+        if b == *kv {
+            return Err(SrpAuthError::IllegalParameter("b_pub"));
+        }
+        let exponent = SecretUint(u * &*x + &*a);
+        let premaster = SecretUint(((n + b - &*kv) % n).modpow(&exponent, n));
+        if *premaster <= BigUint::from(1u8) {
+            return Err(SrpAuthError::IllegalParameter("premaster_secret"));
+        }
+        let premaster = to_fixed_bytes(&premaster, params.byte_len());
+        let key = Zeroizing::new(xof::<X>(&[trim_leading_zeros(&premaster)], self.key_len));
+
+        let mut group_hash = self.hash(&[&n.to_bytes_be()]);
+        let g_hash = self.hash(&[&params.pad_to_group(&params.g.to_bytes_be())]);
+        for (byte, g) in group_hash.iter_mut().zip(g_hash) {
+            *byte ^= g;
+        }
+        let username_hash = self.hash(&[username]);
+        let m1 = self.hash(&[&group_hash, &username_hash, salt, &a_bytes, &b_bytes, &key]);
+        let m2 = self.hash(&[&a_bytes, &m1, &key]);
+        Ok(XofSrpClientVerifier { m1, m2, key })
+    }
+}
+
+impl XofSrpClientVerifier {
+    pub fn proof(&self) -> &[u8] {
+        &self.m1
+    }
+
+    pub fn verify_server(self, reply: &[u8]) -> Result<Zeroizing<Vec<u8>>, SrpAuthError> {
+        if !constant_time_eq(&self.m2, reply) {
+            return Err(SrpAuthError::BadRecordMac("server"));
+        }
+        Ok(self.key)
+    }
+}
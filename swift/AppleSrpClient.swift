@@ -0,0 +1,59 @@
+import Foundation
+
+// Data-based wrapper over the swift-bridge glue generated by the `swift`
+// feature. Add this file next to the generated apple_srp_client.swift and
+// SwiftBridgeCore.swift.
+
+extension SwiftSrpError: @unchecked Sendable, Error, CustomStringConvertible {
+    public var description: String {
+        message().toString()
+    }
+}
+
+private func rustVec(_ data: Data) -> RustVec<UInt8> {
+    let vec = RustVec<UInt8>()
+    for byte in data {
+        vec.push(value: byte)
+    }
+    return vec
+}
+
+public final class AppleSrpVerifier {
+    private let inner: SwiftSrpVerifier
+
+    fileprivate init(_ inner: SwiftSrpVerifier) {
+        self.inner = inner
+    }
+
+    public var proof: Data {
+        Data(inner.proof())
+    }
+
+    public func verifyServer(_ m2: Data) throws -> Data {
+        Data(try inner.verify_server(rustVec(m2)))
+    }
+}
+
+public final class AppleSrpClient {
+    private let inner: SwiftSrpClient
+
+    public init(profile: String = "", digest: String = "") throws {
+        inner = try srp_client_new(profile, digest)
+    }
+
+    public func computeVerifier(username: Data, password: Data, salt: Data) -> Data {
+        Data(inner.compute_verifier(rustVec(username), rustVec(password), rustVec(salt)))
+    }
+
+    public func computePublicEphemeral(_ a: Data) throws -> Data {
+        Data(try inner.compute_public_ephemeral(rustVec(a)))
+    }
+
+    public func processReply(
+        a: Data, username: Data, password: Data, salt: Data, bPub: Data
+    ) throws -> AppleSrpVerifier {
+        let verifier = try inner.process_reply(
+            rustVec(a), rustVec(username), rustVec(password), rustVec(salt), rustVec(bPub))
+        return AppleSrpVerifier(verifier)
+    }
+}
@@ -0,0 +1,254 @@
+use apple_srp_client::{
+    BigUint, CompatProfile, Digest, DigestAlgorithm, DynSrpClient, G_2048, SrpClient, SrpGroup,
+    SrpOptions, SrpServer,
+};
+use blake2::Blake2b512;
+use sha1::Sha1;
+use sha2::{Sha256, Sha512};
+use sha3::{Sha3_256, Sha3_512};
+
+// Known answers over the RFC 5054 1024-bit group with the inputs of RFC 5054
+// appendix B. They were computed outside the crate, with a short Python
+// SRP-6a on hashlib and pow that reproduces the appendix's k, x, u, v, A, B
+// and premaster secret, so a digest mixed up anywhere in the crate changes
+// the answer.
+const USERNAME: &[u8] = b"alice";
+const PASSWORD: &[u8] = b"password123";
+const SALT: &str = "BEB25379D1A8581EB5A727673A2441EE";
+const A: &str = "60975527035CF2AD1989806F0407210BC81EDC04E2762A56AFD529DDDA2D4393";
+const B: &str = "E487CB59D31AC550471E81F00F6928E01DDA08E974A004F49E61F5D105284D20";
+
+// v, A and B as printed in RFC 5054 appendix B.
+const RFC5054_V: &str = concat!(
+    "7E273DE8696FFC4F4E337D05B4B375BEB0DDE1569E8FA00A9886D8129BADA1F1",
+    "822223CA1A605B530E379BA4729FDC59F105B4787E5186F5C671085A1447B52A",
+    "48CF1970B4FB6F8400BBF4CEBFBB168152E08AB5EA53D15C1AFF87B2B9DA6E04",
+    "E058AD51CC72BFC9033B564E26480D78E955A5E29E7AB245DB2BE315E2099AFB",
+);
+const RFC5054_A: &str = concat!(
+    "61D5E490F6F1B79547B0704C436F523DD0E560F0C64115BB72557EC44352E890",
+    "3211C04692272D8B2D1A5358A2CF1B6E0BFCF99F921530EC8E39356179EAE45E",
+    "42BA92AEACED825171E1E8B9AF6D9C03E1327F44BE087EF06530E69F66615261",
+    "EEF54073CA11CF5858F0EDFDFE15EFEAB349EF5D76988A3672FAC47B0769447B",
+);
+const RFC5054_B: &str = concat!(
+    "BD0C61512C692C0CB6D041FA01BB152D4916A1E77AF46AE105393011BAF38964",
+    "DC46A0670DD125B95A981652236F99D9B681CBF87837EC996C6DA04453728610",
+    "D0C6DDB58B318885D7D82C7F8DEB75CE7BD4FBAA37089E6F9C6059F388838E7A",
+    "00030B331EB76840910440B1B27AAEAEEB4012B7D7665238A8E3FB004B117B58",
+);
+
+struct Vector {
+    b_pub: &'static str,
+    key: &'static str,
+    m1: &'static str,
+    m2: &'static str,
+}
+
+// The appendix B handshake itself: SHA-1 with u over padded A and B and the
+// username hashed into x.
+const RFC5054_SHA1: Vector = Vector {
+    b_pub: RFC5054_B,
+    key: "017eefa1cefc5c2e626e21598987f31e0f1b11bb",
+    m1: "62c71b289cb22a034b405667e1541202ce5d8e03",
+    m2: "b475d7f2d75ce9537748005483e5d326048b59e9",
+};
+
+// The same inputs under the default options, once per digest: x leaves the
+// username out and u is over unpadded A and B.
+const SHA1: Vector = Vector {
+    b_pub: concat!(
+        "689bf74a039d2cf4524d3e7594892c795634513c97b6143f56a14c7cad7b8462",
+        "149d5ec127bc80febdd94905ee39fff2fa39bcf7adadb739c092b874e87acb80",
+        "bed61a808ba41c2f7f62f4faa33068f18346d13fbd15670664b1d633cc6ab2ce",
+        "dd8c2202adbec8d599e8ad80c2e56823d76d5dd51ef1c829e14bf514e1c097ce",
+    ),
+    key: "7119229098f36dcf5c5dbe7fa12a3ff64d3f22c7",
+    m1: "21cb28ec038e7698cddfb013d6aef5940161830f",
+    m2: "a4d0d65b4f5cc22e51f84ed9acacab7c8014169a",
+};
+
+const SHA256: Vector = Vector {
+    b_pub: concat!(
+        "d252d5b5e180f4f65f16f157a2c4ec0e52ce06a53dfc3963f38d9efc8fac8ec7",
+        "12a0f5bbbb52d16a4ecc436b9f772b6df436fd373e5000d2ed95e59c159391cb",
+        "961d67f19306ca95123d26f0c24925d6b66135c7afee0a6a93d953cab3518dcb",
+        "b892f385e3e8a97b264f3d67b31ea891c2e39676e1d0664d879eec71c27b8339",
+    ),
+    key: "0b12b5452aac55a4cfc93cafb96dd7bed4bc72a5ae0b332201b3386f1f9b9796",
+    m1: "84928953293d63f6ba3bd3a965ad527daf6d17e16a998f89162a6a5b12e0f1c7",
+    m2: "7cbb7088b68b95f7b5d06e668b64131e736467b67d4ac2b37509cc866049c5a2",
+};
+
+const SHA512: Vector = Vector {
+    b_pub: concat!(
+        "e4e86ad3425eb1bff42d1c70f4e476337d8940d29dba25823a1e957803178d16",
+        "eacaba7cc9720efdfcb746aee801e92b765df6e14a6e8173c61a3b14f376aa23",
+        "31b4da0719a6f6f12d309a2256c3258733f7e8ab70d5874eab1415b66b306873",
+        "26c975d596821e913d287fb5b014d36d444df5e22d148c0f21b4f477c085eba0",
+    ),
+    key: concat!(
+        "9d75f638c6262bac412401a05b313dd4c234fc9eefca94560f20f918cbddef95",
+        "113637459116c828529bbf4f44e18f6f9a695ecf3c7e63719f7af4e1d703be7f",
+    ),
+    m1: concat!(
+        "2723fd0cdf4725c4669d7be2523ce0f5724675d2d12064fea307f020f8016674",
+        "fd54ce7254d653bc0c5d552e4b0816a1aae19bd59bf9cb927496b28bc52fab05",
+    ),
+    m2: concat!(
+        "996f498f337679e7171d54ed6fe6289186f1e8a4f871548a0de4235d26517d30",
+        "24066cc9eea767f6774666d56b8e874affd2b206b91bbcd29ebb64ba293aa7c4",
+    ),
+};
+
+const SHA3_256: Vector = Vector {
+    b_pub: concat!(
+        "148f663c0342838d7cdba5f254359a11098e5fd3cead13a9c1e0ef9c1fc7a246",
+        "832af9acee85b3ccdc08667026bfbf792e14070e5675cd90c1c8a98b1cfa07c3",
+        "95d7958599a6852537e73d05de5db6e5e63b172bc1fbecad97721d315008d941",
+        "ba6ccbe1295e3d5a495c05f49cf01fa55de47c07f712007d23d3d68af298e80b",
+    ),
+    key: "28871178e2788c101e8cbfe9019e420b97760b1946f8efc595ec0ca5c237e66b",
+    m1: "740a7617345c796899ad2be489c173d2a63da19304fe36c278d20eb7786ac8c1",
+    m2: "b8400ef9b212f5d5407ec7a9e7fa14fa75d620cdf3974246cdd852caae0e52c3",
+};
+
+const SHA3_512: Vector = Vector {
+    b_pub: concat!(
+        "2a32f0a9afbdea819267bd44ff1bc2d481e6baf61f9c60fee4ce67062621dd65",
+        "0ca2870aefa4c4ead0221521c24a2584511d7e8628225f298979d50fbff19a36",
+        "b4cf6732c453dfa8db8cbf769028e6ab0651a39cda7e2ae8a6905ddceed89b5b",
+        "65887fef91ca27e276e31087762c8481356a996f5f53fe9f83c8c1c87d68eb24",
+    ),
+    key: concat!(
+        "f69cf6468d628853c865533aa23aa18dd2f64757cb8c48604f1f811a7deaa163",
+        "291adbb5a5e6bba4d4febddbd1f0a4727231c55d4afbe72b06d0a13bf3268e73",
+    ),
+    m1: concat!(
+        "dc47020063c19a2438209c9969464666fdcdb0cb9dc693e5ecb7e27676122e6f",
+        "15b6c9449c58e35872e2b5c01d8b47c2c63f00e07320db624c6023f5c4a191d7",
+    ),
+    m2: concat!(
+        "2f6b5da2f461d2f3685cb38520db198268aa8fa108f84601854f7281265a6330",
+        "40e79beb145c7bb3e8090c94215c26307ae2768b6472641ff49973b753d40eb1",
+    ),
+};
+
+const BLAKE2B512: Vector = Vector {
+    b_pub: concat!(
+        "e474a3aa9fac7de0f7cae20ae72013d9aaaa3fd856d83769d1e134119972c57e",
+        "9f483065925678a2a10b572a44a28a5ef7e56534330386da151bbfc03aeea8d3",
+        "fb410cbcf96673f82ae7e6b1bc9bb3a7fe962cc8eaec4d8d8263c2cab64a4935",
+        "3edb20ae32447ee639660c27ce8860a1c6ac380a7591bbe7346bb7fd7e0fb84c",
+    ),
+    key: concat!(
+        "1e776a27c129361e37caad42da9d42e06693c83d8d26d7f804a670ee55a135a7",
+        "746441750ce334e90ffb0a31341fe23f2a3e4decb2d60f830221cfca8841dcbd",
+    ),
+    m1: concat!(
+        "3a44a0b293d24fda5236b92078465e3970d9ebabac48ed1efb5abd7415aa4865",
+        "9d773678aa646c09fcc9473d5eebaf01c8513ee27350920ac59be4aa4b82fff3",
+    ),
+    m2: concat!(
+        "9aa6be1ccff73b77edfa894f85937cb425654e7f2b12d8514674fb4aae59ae65",
+        "fc264bffcbc216b5feb28cfccdf5ce77f969eabfb6de0507fc4ffec3027e26e4",
+    ),
+};
+
+fn hex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+fn group_1024() -> SrpGroup {
+    SrpGroup {
+        n: BigUint::from_bytes_be(include_bytes!("../src/1024.bin")),
+        g: BigUint::from(2u8),
+    }
+}
+
+fn known_answer<D: Digest>(options: SrpOptions, vector: &Vector) {
+    let group = group_1024();
+    let client = SrpClient::<D>::with_options(&group, options);
+    let verifier = client
+        .process_reply(&hex(A), USERNAME, PASSWORD, &hex(SALT), &hex(vector.b_pub))
+        .unwrap();
+    assert_eq!(verifier.proof(), hex(vector.m1));
+    let session = verifier.verify_server(&hex(vector.m2)).unwrap();
+    assert_eq!(session.as_bytes(), hex(vector.key));
+}
+
+#[test]
+fn rfc5054_appendix_b() {
+    let group = group_1024();
+    let options = CompatProfile::Rfc5054.options();
+    let client = SrpClient::<Sha1>::with_options(&group, options);
+    let server = SrpServer::<Sha1>::with_options(&group, options);
+    let (salt, v) = (hex(SALT), hex(RFC5054_V));
+
+    assert_eq!(
+        client.compute_public_ephemeral(&hex(A)).unwrap(),
+        hex(RFC5054_A)
+    );
+    assert_eq!(client.compute_verifier(USERNAME, PASSWORD, &salt), v);
+    assert_eq!(
+        server.compute_public_ephemeral(&hex(B), &v).unwrap(),
+        hex(RFC5054_B)
+    );
+    known_answer::<Sha1>(options, &RFC5054_SHA1);
+
+    let (m2, session) = server
+        .process_hello(&hex(B), USERNAME, &salt, &v, &hex(RFC5054_A))
+        .unwrap()
+        .verify_client(&hex(RFC5054_SHA1.m1))
+        .unwrap();
+    assert_eq!(m2[..], hex(RFC5054_SHA1.m2));
+    assert_eq!(session.as_bytes(), hex(RFC5054_SHA1.key));
+}
+
+#[test]
+fn sha1_known_answer() {
+    known_answer::<Sha1>(SrpOptions::default(), &SHA1);
+}
+
+#[test]
+fn sha256_known_answer() {
+    known_answer::<Sha256>(SrpOptions::default(), &SHA256);
+}
+
+#[test]
+fn sha512_known_answer() {
+    known_answer::<Sha512>(SrpOptions::default(), &SHA512);
+}
+
+#[test]
+fn sha3_256_known_answer() {
+    known_answer::<Sha3_256>(SrpOptions::default(), &SHA3_256);
+}
+
+#[test]
+fn sha3_512_known_answer() {
+    known_answer::<Sha3_512>(SrpOptions::default(), &SHA3_512);
+}
+
+#[test]
+fn blake2b_known_answer() {
+    known_answer::<Blake2b512>(SrpOptions::default(), &BLAKE2B512);
+}
+
+#[test]
+fn dyn_client_names() {
+    for (name, size) in [("sha3-256", 32), ("SHA3_512", 64), ("blake2b", 64)] {
+        let algorithm = DigestAlgorithm::from_name(name).unwrap();
+        assert_eq!(algorithm.output_size(), size);
+        let client = DynSrpClient::new(&G_2048, algorithm);
+        let b_pub = BigUint::from(5u8).to_bytes_be();
+        let verifier = client
+            .process_reply(&hex(A), b"alice", b"password", b"salt", &b_pub)
+            .unwrap();
+        assert_eq!(verifier.algorithm(), algorithm);
+        assert_eq!(verifier.proof().len(), size);
+    }
+}
@@ -0,0 +1,82 @@
+use std::{
+    collections::VecDeque,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use apple_srp_client::{
+    DriveError, MOCK_SESSION_KEY, MockSrpClient, PakeTransport, SrpAuthError, drive_pake,
+};
+
+// A scripted peer: records what the client sends and replays canned replies.
+struct Scripted {
+    sent: Vec<Vec<u8>>,
+    replies: VecDeque<Vec<u8>>,
+}
+
+impl PakeTransport for Scripted {
+    type Error = &'static str;
+
+    async fn send(&mut self, message: &[u8]) -> Result<(), &'static str> {
+        self.sent.push(message.to_vec());
+        Ok(())
+    }
+
+    async fn receive(&mut self) -> Result<Vec<u8>, &'static str> {
+        self.replies.pop_front().ok_or("closed")
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+#[test]
+fn drives_handshake_over_borrowed_transport() {
+    let client = MockSrpClient::new();
+    let mut transport = Scripted {
+        sent: Vec::new(),
+        replies: VecDeque::from([
+            client.expected_challenge().unwrap(),
+            client.expected_server_proof().unwrap(),
+        ]),
+    };
+
+    let key = block_on(drive_pake(
+        &client,
+        &mut transport,
+        b"alice",
+        b"pw",
+        &[1; 32],
+    ))
+    .unwrap();
+    assert_eq!(key, MOCK_SESSION_KEY);
+    assert_eq!(transport.sent.len(), 2);
+}
+
+#[test]
+fn surfaces_transport_and_handshake_errors() {
+    let client = MockSrpClient::new();
+    let mut closed = Scripted {
+        sent: Vec::new(),
+        replies: VecDeque::new(),
+    };
+    let err = block_on(drive_pake(&client, &mut closed, b"alice", b"pw", &[1; 32]));
+    assert_eq!(err, Err(DriveError::Transport("closed")));
+
+    let mut forged = Scripted {
+        sent: Vec::new(),
+        replies: VecDeque::from([client.expected_challenge().unwrap(), vec![0xff]]),
+    };
+    let err = block_on(drive_pake(&client, &mut forged, b"alice", b"pw", &[1; 32]));
+    assert!(matches!(
+        err,
+        Err(DriveError::Pake(SrpAuthError::DecodeError(_)))
+    ));
+}
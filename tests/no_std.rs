@@ -0,0 +1,22 @@
+use std::{path::Path, process::Command};
+
+// Builds tests/no_std_dependent, a `#![no_std]` staticlib that depends on this
+// crate with `default-features = false`, the way firmware would.
+#[test]
+fn no_std_dependent_builds() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let output = Command::new(env!("CARGO"))
+        .arg("build")
+        .arg("--manifest-path")
+        .arg(root.join("tests/no_std_dependent/Cargo.toml"))
+        .arg("--target-dir")
+        .arg(root.join("target/no_std_dependent"))
+        .env_remove("RUSTFLAGS")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
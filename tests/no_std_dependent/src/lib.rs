@@ -0,0 +1,44 @@
+// Links apple_srp_client into a staticlib that supplies its own panic
+// handler and allocator. If anything in the dependency graph pulled in std,
+// its panic handler would clash with this one and the build would fail.
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::{
+    alloc::{GlobalAlloc, Layout},
+    panic::PanicInfo,
+    ptr,
+};
+
+use apple_srp_client::{DigestAlgorithm, DynSrpClient, SrpGroup, SrpOptions};
+
+struct NoAlloc;
+
+unsafe impl GlobalAlloc for NoAlloc {
+    unsafe fn alloc(&self, _layout: Layout) -> *mut u8 {
+        ptr::null_mut()
+    }
+
+    unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {}
+}
+
+#[global_allocator]
+static ALLOCATOR: NoAlloc = NoAlloc;
+
+#[panic_handler]
+fn panic(_info: &PanicInfo) -> ! {
+    loop {}
+}
+
+// Instantiates the client for every digest, so the whole core is compiled
+// rather than only type-checked.
+#[unsafe(no_mangle)]
+pub extern "C" fn public_ephemeral_len(a: *const u8, a_len: usize) -> usize {
+    let a = unsafe { core::slice::from_raw_parts(a, a_len) };
+    let group = SrpGroup::rfc5054_2048();
+    let client = DynSrpClient::with_options(&group, DigestAlgorithm::Sha256, SrpOptions::default());
+    let a_pub: Vec<u8> = client.compute_public_ephemeral(a).unwrap_or_default();
+    a_pub.len()
+}
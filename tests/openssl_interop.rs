@@ -0,0 +1,174 @@
+use std::ffi::{CString, c_char};
+
+use apple_srp_client::{CompatProfile, G_2048, G_3072, SrpClient, SrpServer};
+use foreign_types::{ForeignType, ForeignTypeRef};
+use openssl::{
+    bn::{BigNum, BigNumContext, BigNumRef},
+    sha::{Sha1, sha1},
+};
+use openssl_sys::BIGNUM;
+
+// The openssl crate has no SRP API, so only the SRP_Calc_* helpers are
+// declared here; everything else goes through the safe bindings.
+#[repr(C)]
+struct SrpGn {
+    id: *mut c_char,
+    g: *mut BIGNUM,
+    n: *mut BIGNUM,
+}
+
+unsafe extern "C" {
+    fn SRP_get_default_gN(id: *const c_char) -> *mut SrpGn;
+    fn SRP_Calc_x(s: *const BIGNUM, user: *const c_char, pass: *const c_char) -> *mut BIGNUM;
+    fn SRP_Calc_A(a: *const BIGNUM, n: *const BIGNUM, g: *const BIGNUM) -> *mut BIGNUM;
+    fn SRP_Calc_B(
+        b: *const BIGNUM,
+        n: *const BIGNUM,
+        g: *const BIGNUM,
+        v: *const BIGNUM,
+    ) -> *mut BIGNUM;
+    fn SRP_Calc_u(a: *const BIGNUM, b: *const BIGNUM, n: *const BIGNUM) -> *mut BIGNUM;
+    fn SRP_Calc_server_key(
+        a: *const BIGNUM,
+        v: *const BIGNUM,
+        u: *const BIGNUM,
+        b: *const BIGNUM,
+        n: *const BIGNUM,
+    ) -> *mut BIGNUM;
+}
+
+fn owned(bn: *mut BIGNUM) -> BigNum {
+    assert!(!bn.is_null());
+    unsafe { BigNum::from_ptr(bn) }
+}
+
+struct Handshake {
+    salt: Vec<u8>,
+    a: Vec<u8>,
+    a_pub: Vec<u8>,
+    b: Vec<u8>,
+    b_pub: Vec<u8>,
+    v: Vec<u8>,
+    key: [u8; 20],
+    m1: [u8; 20],
+    m2: [u8; 20],
+}
+
+fn openssl_handshake(username: &str, password: &str, seed: u8) -> Handshake {
+    let id = CString::new("2048").unwrap();
+    let gn = unsafe { &*SRP_get_default_gN(id.as_ptr()) };
+    let (n, g) = unsafe { (BigNumRef::from_ptr(gn.n), BigNumRef::from_ptr(gn.g)) };
+    assert_eq!(n.to_vec(), G_2048.n.to_bytes_be());
+
+    let salt = BigNum::from_slice(&[seed; 16]).unwrap();
+    let a = BigNum::from_slice(&[0x80 | seed.wrapping_add(1); 32]).unwrap();
+    let b = BigNum::from_slice(&[seed.wrapping_add(2); 32]).unwrap();
+    let user = CString::new(username).unwrap();
+    let pass = CString::new(password).unwrap();
+
+    let x = owned(unsafe { SRP_Calc_x(salt.as_ptr(), user.as_ptr(), pass.as_ptr()) });
+    let mut v = BigNum::new().unwrap();
+    v.mod_exp(g, &x, n, &mut BigNumContext::new().unwrap())
+        .unwrap();
+
+    let a_pub = owned(unsafe { SRP_Calc_A(a.as_ptr(), n.as_ptr(), g.as_ptr()) });
+    let b_pub = owned(unsafe { SRP_Calc_B(b.as_ptr(), n.as_ptr(), g.as_ptr(), v.as_ptr()) });
+    let u = owned(unsafe { SRP_Calc_u(a_pub.as_ptr(), b_pub.as_ptr(), n.as_ptr()) });
+    let premaster = owned(unsafe {
+        SRP_Calc_server_key(
+            a_pub.as_ptr(),
+            v.as_ptr(),
+            u.as_ptr(),
+            b.as_ptr(),
+            n.as_ptr(),
+        )
+    });
+
+    // M1 = H(H(N) ^ H(PAD(g)) | H(I) | s | A | B | K), M2 = H(A | M1 | K),
+    // built here from the OpenSSL values rather than the crate's helpers.
+    let (salt, a_pub, b_pub) = (salt.to_vec(), a_pub.to_vec(), b_pub.to_vec());
+    let key = sha1(&premaster.to_vec());
+    let mut group_hash = sha1(&g.to_vec_padded(n.num_bytes()).unwrap());
+    for (byte, n_byte) in group_hash.iter_mut().zip(sha1(&n.to_vec())) {
+        *byte ^= n_byte;
+    }
+    let mut m1 = Sha1::new();
+    for part in [
+        &group_hash[..],
+        &sha1(username.as_bytes()),
+        &salt,
+        &a_pub,
+        &b_pub,
+        &key,
+    ] {
+        m1.update(part);
+    }
+    let m1 = m1.finish();
+    let mut m2 = Sha1::new();
+    for part in [&a_pub[..], &m1, &key] {
+        m2.update(part);
+    }
+
+    Handshake {
+        salt,
+        a: a.to_vec(),
+        a_pub,
+        b: b.to_vec(),
+        b_pub,
+        v: v.to_vec(),
+        key,
+        m1,
+        m2: m2.finish(),
+    }
+}
+
+#[test]
+fn openssl_profile_matches_srp_calc() {
+    for (seed, username, password) in [(1, "alice", "password123"), (0x80, "bob", "hunter2")] {
+        let hs = openssl_handshake(username, password, seed);
+        let client =
+            SrpClient::<sha1::Sha1>::with_options(&G_2048, CompatProfile::OpenSsl.options());
+
+        assert_eq!(client.compute_public_ephemeral(&hs.a).unwrap(), hs.a_pub);
+        assert_eq!(
+            client.compute_verifier(username.as_bytes(), password.as_bytes(), &hs.salt),
+            hs.v
+        );
+
+        let verifier = client
+            .process_reply(
+                &hs.a,
+                username.as_bytes(),
+                password.as_bytes(),
+                &hs.salt,
+                &hs.b_pub,
+            )
+            .unwrap();
+        assert_eq!(verifier.proof(), hs.m1);
+        let session = verifier.verify_server(&hs.m2).unwrap();
+        assert_eq!(session.as_bytes(), hs.key);
+
+        let server =
+            SrpServer::<sha1::Sha1>::with_options(&G_2048, CompatProfile::OpenSsl.options());
+        assert_eq!(
+            server.compute_public_ephemeral(&hs.b, &hs.v).unwrap(),
+            hs.b_pub
+        );
+        let (m2, session) = server
+            .process_hello(&hs.b, username.as_bytes(), &hs.salt, &hs.v, &hs.a_pub)
+            .unwrap()
+            .verify_client(&hs.m1)
+            .unwrap();
+        assert_eq!(m2[..], hs.m2);
+        assert_eq!(session.as_bytes(), hs.key);
+    }
+}
+
+#[test]
+fn group_3072_matches_openssl() {
+    let id = CString::new("3072").unwrap();
+    let gn = unsafe { &*SRP_get_default_gN(id.as_ptr()) };
+    let (n, g) = unsafe { (BigNumRef::from_ptr(gn.n), BigNumRef::from_ptr(gn.g)) };
+    assert_eq!(n.to_vec(), G_3072.n.to_bytes_be());
+    assert_eq!(g.to_vec(), G_3072.g.to_bytes_be());
+}
@@ -0,0 +1,218 @@
+use apple_srp_client::{
+    BigUint, CompatProfile, G_2048, G_3072, HapError, PAIR_SETUP_M2, PAIR_SETUP_M4,
+    PAIR_SETUP_USERNAME, PairSetupOutcome, PairSetupResponder, SetupCode, SrpAuthError, SrpClient,
+    SrpServer, TLV_ERROR, TLV_METHOD, TLV_PROOF, TLV_PUBLIC_KEY, TLV_SALT, TLV_STATE, compute_k,
+    decode_tlv8, encode_tlv8, pair_setup_options,
+};
+use rand_core::{CryptoRng, RngCore, impls};
+use sha2::{Sha256, Sha512};
+
+const A: [u8; 32] = [0x31; 32];
+const B: [u8; 32] = [0x42; 32];
+
+struct CounterRng(u64);
+
+impl RngCore for CounterRng {
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1);
+        self.0
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for CounterRng {}
+
+fn field(items: &[(u8, Vec<u8>)], ty: u8) -> &[u8] {
+    &items.iter().find(|(item_ty, _)| *item_ty == ty).unwrap().1
+}
+
+#[test]
+fn server_answers_every_profile() {
+    for profile in [
+        CompatProfile::Apple,
+        CompatProfile::Rfc5054,
+        CompatProfile::PySrp,
+        CompatProfile::NodeSrp,
+        CompatProfile::Thinbus,
+        CompatProfile::OpenSsl,
+    ] {
+        let client = SrpClient::<Sha256>::with_options(&G_2048, profile.options());
+        let server = SrpServer::<Sha256>::with_options(&G_2048, profile.options());
+        let registration = client.register(b"alice", b"pw", &mut CounterRng(1));
+
+        let b_pub = server
+            .compute_public_ephemeral(&B, &registration.verifier)
+            .unwrap();
+        let a_pub = client.compute_public_ephemeral(&A).unwrap();
+        let verifier = client
+            .process_reply(&A, b"alice", b"pw", &registration.salt, &b_pub)
+            .unwrap();
+        let (m2, server_key) = server
+            .process_hello(
+                &B,
+                b"alice",
+                &registration.salt,
+                &registration.verifier,
+                &a_pub,
+            )
+            .unwrap()
+            .verify_client(verifier.proof())
+            .unwrap();
+        let client_key = verifier.verify_server(&m2).unwrap();
+        assert_eq!(client_key.as_bytes(), server_key.as_bytes(), "{profile:?}");
+    }
+}
+
+#[test]
+fn server_b_is_kv_plus_g_to_the_b() {
+    let client = SrpClient::<Sha256>::new(&G_2048);
+    let server = SrpServer::<Sha256>::new(&G_2048);
+    let registration = client.register(b"alice", b"pw", &mut CounterRng(1));
+
+    let v = BigUint::from_bytes_be(&registration.verifier);
+    let b = BigUint::from_bytes_be(&B);
+    let expected = (compute_k::<Sha256>(&G_2048) * v + G_2048.g.modpow(&b, &G_2048.n)) % &G_2048.n;
+    let b_pub = server
+        .compute_public_ephemeral(&B, &registration.verifier)
+        .unwrap();
+    assert_eq!(BigUint::from_bytes_be(&b_pub), expected);
+}
+
+#[test]
+fn server_rejects_wrong_password_and_bad_inputs() {
+    let client = SrpClient::<Sha256>::new(&G_2048);
+    let server = SrpServer::<Sha256>::new(&G_2048);
+    let registration = client.register(b"alice", b"pw", &mut CounterRng(1));
+    let (salt, verifier) = (&registration.salt, &registration.verifier);
+
+    let b_pub = server.compute_public_ephemeral(&B, verifier).unwrap();
+    let a_pub = client.compute_public_ephemeral(&A).unwrap();
+    let wrong = client
+        .process_reply(&A, b"alice", b"px", salt, &b_pub)
+        .unwrap();
+    let hello = server
+        .process_hello(&B, b"alice", salt, verifier, &a_pub)
+        .unwrap();
+    assert_eq!(
+        hello.verify_client(wrong.proof()).err(),
+        Some(SrpAuthError::BadRecordMac("client"))
+    );
+
+    let n = G_2048.n.to_bytes_be();
+    assert_eq!(
+        server.process_hello(&B, b"alice", salt, verifier, &n).err(),
+        Some(SrpAuthError::IllegalParameter("a_pub"))
+    );
+    assert_eq!(
+        server.compute_public_ephemeral(&[0; 32], verifier).err(),
+        Some(SrpAuthError::IllegalParameter("b"))
+    );
+    assert_eq!(
+        server.compute_public_ephemeral(&B, &[0]).err(),
+        Some(SrpAuthError::IllegalParameter("verifier"))
+    );
+}
+
+#[test]
+fn server_channel_binding_must_match() {
+    let client = SrpClient::<Sha256>::new(&G_2048).with_channel_binding(b"exporter one");
+    let server = SrpServer::<Sha256>::new(&G_2048);
+    let registration = client.register(b"alice", b"pw", &mut CounterRng(1));
+    let (salt, verifier) = (&registration.salt, &registration.verifier);
+
+    let b_pub = server.compute_public_ephemeral(&B, verifier).unwrap();
+    let a_pub = client.compute_public_ephemeral(&A).unwrap();
+    let proof = client
+        .process_reply(&A, b"alice", b"pw", salt, &b_pub)
+        .unwrap();
+    for (binding, accepted) in [(&b"exporter one"[..], true), (b"exporter two", false)] {
+        let hello = server
+            .clone()
+            .with_channel_binding(binding)
+            .process_hello(&B, b"alice", salt, verifier, &a_pub)
+            .unwrap();
+        assert_eq!(hello.verify_client(proof.proof()).is_ok(), accepted);
+    }
+}
+
+fn pair_setup(responder: &PairSetupResponder<'_, Sha512>, code: &str) -> PairSetupOutcome<Sha512> {
+    let m1 = encode_tlv8(&[(TLV_STATE, &[1]), (TLV_METHOD, &[0])]);
+    let (m2, challenge) = responder.respond_m1(&m1, &[0x77; 32]).unwrap();
+    let m2 = decode_tlv8(&m2).unwrap();
+    assert_eq!(field(&m2, TLV_STATE), [PAIR_SETUP_M2]);
+
+    let controller = SrpClient::<Sha512>::with_options(&G_3072, pair_setup_options());
+    let a = [0x55; 32];
+    let a_pub = controller.compute_public_ephemeral(&a).unwrap();
+    let proof = controller
+        .process_reply(
+            &a,
+            PAIR_SETUP_USERNAME,
+            code.as_bytes(),
+            field(&m2, TLV_SALT),
+            field(&m2, TLV_PUBLIC_KEY),
+        )
+        .unwrap();
+    let m3 = encode_tlv8(&[
+        (TLV_STATE, &[3]),
+        (TLV_PUBLIC_KEY, &a_pub),
+        (TLV_PROOF, proof.proof()),
+    ]);
+    let outcome = responder.respond_m3(challenge, &m3).unwrap();
+    if let PairSetupOutcome::Paired { response, key } = &outcome {
+        let m4 = decode_tlv8(response).unwrap();
+        assert_eq!(field(&m4, TLV_STATE), [PAIR_SETUP_M4]);
+        let controller_key = proof.verify_server(field(&m4, TLV_PROOF)).unwrap();
+        assert_eq!(controller_key.as_bytes(), key.as_bytes());
+    }
+    outcome
+}
+
+#[test]
+fn pair_setup_responder_runs_m1_to_m4() {
+    let code = SetupCode::parse("031-45-154").unwrap();
+    let responder =
+        PairSetupResponder::<Sha512>::from_setup_code(&G_3072, code, &mut CounterRng(1));
+    assert!(matches!(
+        pair_setup(&responder, "031-45-154"),
+        PairSetupOutcome::Paired { .. }
+    ));
+
+    let PairSetupOutcome::Rejected { response } = pair_setup(&responder, "031-45-155") else {
+        panic!("wrong setup code accepted");
+    };
+    let m4 = decode_tlv8(&response).unwrap();
+    assert_eq!(field(&m4, TLV_STATE), [PAIR_SETUP_M4]);
+    assert_eq!(field(&m4, TLV_ERROR), [HapError::Authentication.code()]);
+
+    let restored =
+        PairSetupResponder::<Sha512>::new(&G_3072, responder.salt(), responder.verifier());
+    assert!(matches!(
+        pair_setup(&restored, "031-45-154"),
+        PairSetupOutcome::Paired { .. }
+    ));
+}
+
+#[test]
+fn pair_setup_responder_refuses_other_states() {
+    let code = SetupCode::parse("031-45-154").unwrap();
+    let responder =
+        PairSetupResponder::<Sha512>::from_setup_code(&G_3072, code, &mut CounterRng(1));
+    let m3 = encode_tlv8(&[(TLV_STATE, &[3]), (TLV_METHOD, &[0])]);
+    assert_eq!(
+        responder.respond_m1(&m3, &[0x77; 32]).err(),
+        Some(SrpAuthError::DecodeError("pair_setup_m1"))
+    );
+}